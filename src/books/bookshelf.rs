@@ -0,0 +1,136 @@
+use std::fs::{self, read_to_string};
+use std::io;
+use std::path::Path;
+
+use crate::char_lib::layouts::KeyboardLayout;
+use crate::char_lib::load_text;
+
+static APP_NAME: &str = "crabtype";
+static BOOKS_NAME: &str = "books";
+
+/// a long text that is typed over many sessions, with a bookmark and the
+/// stats of every finished passage
+#[derive(Debug, Clone, Default, serde_derive::Serialize, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct Book {
+    pub name: String,
+    pub path: String,
+    // character offset of the bookmark in the normalised text
+    pub offset: usize,
+    pub length: usize,
+    pub sessions: u32,
+    pub keystrokes: u32,
+    pub correct: u32,
+    pub duration_ms: i64,
+}
+
+impl Book {
+    /// the book text with the same normalisation as `--text`
+    fn read_text(&self, layout: &KeyboardLayout) -> io::Result<String> {
+        let text = read_to_string(&self.path)?;
+        Ok(load_text::normalise(&text, layout).0)
+    }
+
+    /// the passage starting at the bookmark
+    pub fn next_passage(&self, layout: &KeyboardLayout) -> io::Result<String> {
+        let text: String = self.read_text(layout)?.chars().skip(self.offset).collect();
+        Ok(load_text::split_sessions(&text)
+            .into_iter()
+            .next()
+            .unwrap_or_default())
+    }
+
+    /// moves the bookmark past a finished passage and adds its stats
+    pub fn finish_passage(
+        &mut self,
+        passage_len: usize,
+        keystrokes: u32,
+        correct: u32,
+        duration_ms: i64,
+    ) {
+        // the space after the passage is skipped as well
+        self.offset = (self.offset + passage_len + 1).min(self.length);
+        self.sessions += 1;
+        self.keystrokes += keystrokes;
+        self.correct += correct;
+        self.duration_ms += duration_ms;
+    }
+
+    /// percentage of the book that has been typed
+    pub fn progress(&self) -> f64 {
+        100.0 * self.offset as f64 / self.length.max(1) as f64
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.offset >= self.length
+    }
+
+    /// correct characters per minute over all sessions
+    pub fn cpm(&self) -> f64 {
+        if self.duration_ms <= 0 {
+            return 0.0;
+        }
+        f64::from(self.correct) * 60_000.0 / self.duration_ms as f64
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        100.0 * f64::from(self.correct) / f64::from(self.keystrokes.max(1))
+    }
+}
+
+#[derive(Debug, Clone, Default, serde_derive::Serialize, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct Bookshelf {
+    pub books: Vec<Book>,
+}
+
+impl Bookshelf {
+    pub fn read() -> Result<Bookshelf, confy::ConfyError> {
+        confy::load(APP_NAME, BOOKS_NAME)
+    }
+
+    pub fn write(&self) -> Result<(), confy::ConfyError> {
+        confy::store(APP_NAME, BOOKS_NAME, self)
+    }
+
+    /// the books that have not been typed to the end
+    pub fn in_progress(&self) -> Vec<Book> {
+        self.books
+            .iter()
+            .filter(|book| !book.is_finished())
+            .cloned()
+            .collect()
+    }
+
+    /// puts a book on the shelf and returns it, a book that is already there
+    /// keeps its bookmark
+    pub fn add(&mut self, path: &Path, layout: &KeyboardLayout) -> io::Result<Book> {
+        let path = fs::canonicalize(path)?.to_string_lossy().to_string();
+        if let Some(book) = self.books.iter().find(|book| book.path == path) {
+            return Ok(book.clone());
+        }
+        let mut book = Book {
+            name: Path::new(&path)
+                .file_stem()
+                .map_or("book".to_string(), |name| name.to_string_lossy().to_string()),
+            path,
+            ..Book::default()
+        };
+        book.length = book.read_text(layout)?.chars().count();
+        if book.length == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} contains no text that can be typed", book.name),
+            ));
+        }
+        self.books.push(book.clone());
+        Ok(book)
+    }
+
+    /// replaces the stored book with the same path
+    pub fn update(&mut self, book: &Book) {
+        if let Some(stored) = self.books.iter_mut().find(|b| b.path == book.path) {
+            *stored = book.clone();
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::history::session_history::SessionRecord;
+
+// a session counts half as much after this many days
+static HALF_LIFE_DAYS: f64 = 7.0;
+// how much a 100% error rate and a doubled latency add to the base weight of 1
+static ERROR_WEIGHT: f64 = 4.0;
+static LATENCY_WEIGHT: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DecayedKey {
+    attempts: f64,
+    misses: f64,
+    hits: f64,
+    latency_ms: f64,
+}
+
+/// weights for drawing each character of `char_vec`, higher for characters the
+/// user historically misses or types slowly. Recent sessions count more than
+/// old ones. Returns `None` when the history holds nothing about these characters.
+pub fn char_weights(char_vec: &[char], sessions: &[SessionRecord], now: i64) -> Option<Vec<f64>> {
+    let mut keys: Vec<DecayedKey> = vec![DecayedKey::default(); char_vec.len()];
+    for session in sessions {
+        let age_days = (now - session.timestamp).max(0) as f64 / 86_400.0;
+        let decay = 0.5_f64.powf(age_days / HALF_LIFE_DAYS);
+        for result in &session.key_results {
+            for (i, _) in char_vec.iter().enumerate().filter(|(_, c)| **c == result.key) {
+                keys[i].attempts += decay * f64::from(result.hits + result.misses);
+                keys[i].misses += decay * f64::from(result.misses);
+                keys[i].hits += decay * f64::from(result.hits);
+                keys[i].latency_ms += decay * result.total_latency_ms as f64;
+            }
+        }
+    }
+
+    if keys.iter().all(|k| k.attempts == 0.0) {
+        return None;
+    }
+
+    let total_hits: f64 = keys.iter().map(|k| k.hits).sum();
+    let mean_latency = if total_hits > 0.0 {
+        keys.iter().map(|k| k.latency_ms).sum::<f64>() / total_hits
+    } else {
+        0.0
+    };
+
+    Some(
+        keys.iter()
+            .map(|k| {
+                let error_rate = if k.attempts > 0.0 {
+                    k.misses / k.attempts
+                } else {
+                    0.0
+                };
+                let slowness = if k.hits > 0.0 && mean_latency > 0.0 {
+                    (k.latency_ms / k.hits / mean_latency - 1.0).max(0.0)
+                } else {
+                    0.0
+                };
+                1.0 + ERROR_WEIGHT * error_rate + LATENCY_WEIGHT * slowness
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use crate::char_lib::resources;
+
+static CHAR_SET_DIR: &str = "charsets";
+
+/// a character set users added to a `charsets` directory of the resource search path,
+/// either as a `.txt` file with the characters or as a `.toml` file like
+///
+/// ```toml
+/// name = "Greek"
+/// description = "lowercase greek letters"
+/// characters = "αβγδε"
+/// weights = { "α" = 2.0 }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharSet {
+    // file name without extension, used in the settings
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub chars: Vec<char>,
+    // how often each character is drawn compared to the others
+    pub weights: Vec<f64>,
+}
+
+#[derive(Debug, Default, serde_derive::Deserialize)]
+#[serde(default)]
+struct CharSetFile {
+    name: Option<String>,
+    description: String,
+    characters: String,
+    weights: HashMap<String, f64>,
+}
+
+impl CharSet {
+    fn from_txt(id: &str, contents: &str) -> Option<CharSet> {
+        let chars: Vec<char> = contents.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.is_empty() {
+            return None;
+        }
+        Some(CharSet {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            weights: vec![1.0; chars.len()],
+            chars,
+        })
+    }
+
+    fn from_toml(id: &str, contents: &str) -> Option<CharSet> {
+        let file: CharSetFile = toml::from_str(contents).ok()?;
+        let chars: Vec<char> = file.characters.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.is_empty() {
+            return None;
+        }
+        let weights = chars
+            .iter()
+            .map(|c| {
+                file.weights
+                    .get(&c.to_string())
+                    .copied()
+                    .unwrap_or(1.0)
+                    .max(0.0)
+            })
+            .collect();
+        Some(CharSet {
+            id: id.to_string(),
+            name: file.name.unwrap_or_else(|| id.to_string()),
+            description: file.description,
+            chars,
+            weights,
+        })
+    }
+}
+
+/// every valid character set in the `charsets` directories, sorted by id.
+/// A `.toml` file takes precedence over a `.txt` file with the same name.
+pub fn custom_char_sets() -> Vec<CharSet> {
+    let mut sets: Vec<CharSet> = resources::user_resources(CHAR_SET_DIR, "toml")
+        .iter()
+        .filter_map(|(id, contents)| CharSet::from_toml(id, contents))
+        .collect();
+    for (id, contents) in resources::user_resources(CHAR_SET_DIR, "txt") {
+        if sets.iter().any(|set| set.id == id) {
+            continue;
+        }
+        if let Some(set) = CharSet::from_txt(&id, &contents) {
+            sets.push(set);
+        }
+    }
+    sets.sort_by(|a, b| a.id.cmp(&b.id));
+    sets
+}
@@ -0,0 +1,139 @@
+use ratatui::style::Color;
+
+use crate::char_lib::layouts::KeyboardLayout;
+use crate::game::metrics::Keystroke;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    Thumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+impl Finger {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Finger::LeftPinky => "left pinky",
+            Finger::LeftRing => "left ring",
+            Finger::LeftMiddle => "left middle",
+            Finger::LeftIndex => "left index",
+            Finger::Thumb => "thumb",
+            Finger::RightIndex => "right index",
+            Finger::RightMiddle => "right middle",
+            Finger::RightRing => "right ring",
+            Finger::RightPinky => "right pinky",
+        }
+    }
+
+    pub fn hand(&self) -> Option<Hand> {
+        match self {
+            Finger::LeftPinky | Finger::LeftRing | Finger::LeftMiddle | Finger::LeftIndex => {
+                Some(Hand::Left)
+            }
+            Finger::Thumb => None,
+            _ => Some(Hand::Right),
+        }
+    }
+
+    /// the same color is used for a finger on both hands
+    pub fn color(&self) -> Color {
+        match self {
+            Finger::LeftPinky | Finger::RightPinky => Color::Rgb(190, 66, 190),
+            Finger::LeftRing | Finger::RightRing => Color::Rgb(66, 128, 190),
+            Finger::LeftMiddle | Finger::RightMiddle => Color::Rgb(66, 190, 66),
+            Finger::LeftIndex | Finger::RightIndex => Color::Rgb(220, 160, 40),
+            Finger::Thumb => Color::Rgb(128, 128, 128),
+        }
+    }
+
+    /// the finger that types the key at `column` of keyboard row `row`
+    pub fn for_position(row: usize, column: usize) -> Finger {
+        // the number row is shifted one column to the left of the letter rows
+        let column = if row == 0 { column.saturating_sub(1) } else { column };
+        match column {
+            0 => Finger::LeftPinky,
+            1 => Finger::LeftRing,
+            2 => Finger::LeftMiddle,
+            3 | 4 => Finger::LeftIndex,
+            5 | 6 => Finger::RightIndex,
+            7 => Finger::RightMiddle,
+            8 => Finger::RightRing,
+            _ => Finger::RightPinky,
+        }
+    }
+}
+
+pub fn finger_for(layout: &KeyboardLayout, character: char) -> Option<Finger> {
+    if character == ' ' {
+        return Some(Finger::Thumb);
+    }
+    layout
+        .key_position(character).map(|(row, column)| Finger::for_position(row, column))
+}
+
+/// a hint like "left index" or "right pinky + left shift"
+pub fn hint(layout: &KeyboardLayout, character: char) -> Option<String> {
+    let finger = finger_for(layout, character)?;
+    if !layout.needs_shift(character) {
+        return Some(finger.name().to_string());
+    }
+    // shift is pressed with the pinky of the other hand
+    let shift = match finger.hand() {
+        Some(Hand::Left) => "right shift",
+        _ => "left shift",
+    };
+    Some(format!("{} + {shift}", finger.name()))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FingerResult {
+    pub finger: Finger,
+    pub hits: u32,
+    pub misses: u32,
+}
+
+impl FingerResult {
+    /// percentage of correct keystrokes
+    pub fn accuracy(&self) -> f64 {
+        100.0 * f64::from(self.hits) / f64::from((self.hits + self.misses).max(1))
+    }
+}
+
+/// accuracy per finger, for the fingers that were used
+pub fn finger_results(layout: &KeyboardLayout, keystrokes: &[Keystroke]) -> Vec<FingerResult> {
+    let mut results: Vec<FingerResult> = vec![];
+    for key in keystrokes {
+        let Some(finger) = finger_for(layout, key.expected) else {
+            continue;
+        };
+        let index = match results.iter().position(|r| r.finger == finger) {
+            Some(index) => index,
+            None => {
+                results.push(FingerResult {
+                    finger,
+                    hits: 0,
+                    misses: 0,
+                });
+                results.len() - 1
+            }
+        };
+        if key.correct {
+            results[index].hits += 1;
+        } else {
+            results[index].misses += 1;
+        }
+    }
+    results
+}
@@ -0,0 +1,171 @@
+use crate::char_lib::resources;
+use crate::error::AppError;
+
+static LAYOUT_DIR: &str = "layouts";
+
+// name, keys of the four rows without shift, and the same keys with shift
+static BUILTIN: [(&str, [&str; 4], [&str; 4]); 5] = [
+    (
+        "QWERTY",
+        ["`1234567890-=", "qwertyuiop[]\\", "asdfghjkl;'", "zxcvbnm,./"],
+        ["~!@#$%^&*()_+", "QWERTYUIOP{}|", "ASDFGHJKL:\"", "ZXCVBNM<>?"],
+    ),
+    (
+        "Dvorak",
+        ["`1234567890[]", "',.pyfgcrl/=\\", "aoeuidhtns-", ";qjkxbmwvz"],
+        ["~!@#$%^&*(){}", "\"<>PYFGCRL?+|", "AOEUIDHTNS_", ":QJKXBMWVZ"],
+    ),
+    (
+        "Colemak",
+        ["`1234567890-=", "qwfpgjluy;[]\\", "arstdhneio'", "zxcvbkm,./"],
+        ["~!@#$%^&*()_+", "QWFPGJLUY:{}|", "ARSTDHNEIO\"", "ZXCVBKM<>?"],
+    ),
+    (
+        "AZERTY",
+        ["²&é\"'(-è_çà)=", "azertyuiop^$*", "qsdfghjklmù", "wxcvbn,;:!"],
+        ["²1234567890°+", "AZERTYUIOP¨£µ", "QSDFGHJKLM%", "WXCVBN?./§"],
+    ),
+    (
+        "QWERTZ",
+        ["^1234567890ß´", "qwertzuiopü+#", "asdfghjklöä", "yxcvbnm,.-"],
+        ["°!\"§$%&/()=?`", "QWERTZUIOPÜ*'", "ASDFGHJKLÖÄ", "YXCVBNM;:_"],
+    ),
+];
+
+/// the characters on each key of the number row and the three letter rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardLayout {
+    pub name: String,
+    rows: Vec<Vec<char>>,
+    shifted_rows: Vec<Vec<char>>,
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> KeyboardLayout {
+        builtin_layouts().remove(0)
+    }
+}
+
+impl KeyboardLayout {
+    fn from_rows(name: &str, rows: &[&str], shifted_rows: &[&str]) -> Option<KeyboardLayout> {
+        if rows.len() != 4 || shifted_rows.len() != 4 {
+            return None;
+        }
+        let rows: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+        let shifted_rows: Vec<Vec<char>> =
+            shifted_rows.iter().map(|row| row.chars().collect()).collect();
+        if rows.iter().zip(&shifted_rows).any(|(a, b)| a.len() != b.len()) {
+            return None;
+        }
+        Some(KeyboardLayout {
+            name: name.to_string(),
+            rows,
+            shifted_rows,
+        })
+    }
+
+    /// reads a layout file: the four rows without shift followed by the four
+    /// rows with shift, one per line. Lines starting with `#` are ignored.
+    pub fn parse(name: &str, contents: &str) -> Option<KeyboardLayout> {
+        let lines: Vec<&str> = contents
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        if lines.len() != 8 {
+            return None;
+        }
+        KeyboardLayout::from_rows(name, &lines[..4], &lines[4..])
+    }
+
+    pub fn rows(&self) -> &[Vec<char>] {
+        &self.rows
+    }
+
+    /// row and column of the key a character is typed on
+    pub fn key_position(&self, character: char) -> Option<(usize, usize)> {
+        let find = |rows: &[Vec<char>]| {
+            rows.iter().enumerate().find_map(|(row, keys)| {
+                keys.iter()
+                    .position(|c| *c == character)
+                    .map(|column| (row, column))
+            })
+        };
+        find(&self.rows).or_else(|| find(&self.shifted_rows))
+    }
+
+    /// the character on a key when shift is not held
+    pub fn key_at(&self, row: usize, column: usize) -> Option<char> {
+        self.rows.get(row)?.get(column).copied()
+    }
+
+    /// the character on the same key when shift is held
+    pub fn shifted_key_at(&self, row: usize, column: usize) -> Option<char> {
+        self.shifted_rows.get(row)?.get(column).copied()
+    }
+
+    /// the character this layout types on the key that types `character` on `from`,
+    /// e.g. 'd' on QWERTY is 's' on Colemak
+    pub fn translate(&self, from: &KeyboardLayout, character: char) -> char {
+        let Some((row, column)) = from.key_position(character) else {
+            return character;
+        };
+        let key = if from.needs_shift(character) {
+            self.shifted_key_at(row, column)
+        } else {
+            self.key_at(row, column)
+        };
+        key.unwrap_or(character)
+    }
+
+    /// the physical key a character is typed on, e.g. 'A' -> 'a' and '!' -> '1' on QWERTY
+    pub fn base_key(&self, character: char) -> char {
+        match self.key_position(character) {
+            Some((row, column)) => self.key_at(row, column).unwrap_or(character),
+            None => character.to_lowercase().next().unwrap_or(character),
+        }
+    }
+
+    /// whether a character needs shift on this layout
+    pub fn needs_shift(&self, character: char) -> bool {
+        match self.key_position(character) {
+            Some((row, column)) => self.key_at(row, column) != Some(character),
+            None => character.is_uppercase(),
+        }
+    }
+}
+
+fn builtin_layouts() -> Vec<KeyboardLayout> {
+    BUILTIN
+        .iter()
+        .filter_map(|(name, rows, shifted_rows)| {
+            KeyboardLayout::from_rows(name, rows, shifted_rows)
+        })
+        .collect()
+}
+
+/// the built in layouts followed by every valid layout file in a `layouts`
+/// directory of the resource search path
+pub fn available_layouts() -> Vec<KeyboardLayout> {
+    let mut layouts = builtin_layouts();
+    for (name, contents) in resources::user_resources(LAYOUT_DIR, "txt") {
+        if layouts.iter().any(|layout| layout.name == name) {
+            continue;
+        }
+        if let Some(layout) = KeyboardLayout::parse(&name, &contents) {
+            layouts.push(layout);
+        }
+    }
+    layouts
+}
+/// the layout with the given name
+pub fn find_layout(name: &str) -> Result<KeyboardLayout, AppError> {
+    available_layouts()
+        .into_iter()
+        .find(|layout| layout.name == name)
+        .ok_or_else(|| AppError::MissingLayout(name.to_string()))
+}
+
+/// the layout with the given name, QWERTY if it does not exist
+pub fn load_layout(name: &str) -> KeyboardLayout {
+    find_layout(name).unwrap_or_default()
+}
@@ -1,27 +1,41 @@
 use dict::Dict;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
 use rand::seq::SliceRandom;
-use std::fs::read_to_string;
-use std::str;
 
-pub fn load_files_to_vec(dicts: Dict<bool>) -> Vec<char> {
+use crate::char_lib::resources;
+use crate::error::AppError;
+
+pub fn load_files_to_vec(dicts: Dict<bool>) -> Result<Vec<char>, AppError> {
     let mut char_vec: Vec<char> = vec![];
     for i in dicts {
         if i.val {
-            let source_path: String = "resources/".to_owned();
-            let file_type: &str = ".txt";
-            let s: String = read_to_string(source_path + &i.key + file_type).unwrap();
-            let v: Vec<char> = s.chars().collect();
+            let name = i.key + ".txt";
+            let s: String =
+                resources::read_resource(&name).ok_or(AppError::MissingResource(name.clone()))?;
+            // whitespace like the newline at the end of an edited file can't be typed
+            let v: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+            if v.is_empty() {
+                return Err(AppError::EmptyResource(name));
+            }
             for c in v {
                 char_vec.push(c);
             }
         }
     }
-    char_vec
+    Ok(char_vec)
 }
 
-pub fn chose_random(char_vec: Vec<char>) -> char {
-    let output_char: &char = char_vec.choose(&mut rand::thread_rng()).unwrap();
-    output_char.to_owned()
+pub fn chose_random(char_vec: &[char]) -> Option<char> {
+    char_vec.choose(&mut rand::thread_rng()).copied()
+}
+
+/// picks a character with probability proportional to its weight
+pub fn chose_weighted(char_vec: &[char], weights: &[f64]) -> Option<char> {
+    match WeightedIndex::new(weights) {
+        Ok(dist) => char_vec.get(dist.sample(&mut rand::thread_rng())).copied(),
+        Err(_) => chose_random(char_vec),
+    }
 }
 
 // use dict::DictIface;
@@ -30,7 +44,7 @@ pub fn chose_random(char_vec: Vec<char>) -> char {
 //     dict.add("letters".to_string(), true);
 //     dict.add("cap_letters".to_string(), true);
 //     dict.add("numbers".to_string(), true);
-//     let char_vec: Vec<char> = load_files_to_vec(dict);
+//     let char_vec: Vec<char> = load_files_to_vec(dict).unwrap();
 //     println!(
 //         "{:?}",
 //         char_vec
@@ -38,6 +52,41 @@ pub fn chose_random(char_vec: Vec<char>) -> char {
 //             .map(|x| x.to_string() + " ")
 //             .collect::<String>()
 //     );
-//     let rand = chose_random(char_vec);
+//     let rand = chose_random(&char_vec).unwrap();
 //     println!("{rand}");
 // }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use dict::DictIface;
+
+    use super::*;
+
+    /// a resource directory holding `letters.txt` with `contents`, searched by this test
+    fn override_letters(test: &str, contents: &str) {
+        let dir = std::env::temp_dir().join(format!("crabtype-{test}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("letters.txt"), contents).unwrap();
+        resources::TEST_SEARCH_PATH.with(|dirs| *dirs.borrow_mut() = vec![dir]);
+    }
+
+    fn letters() -> Dict<bool> {
+        let mut dict = Dict::<bool>::new();
+        dict.add("letters".to_string(), true);
+        dict
+    }
+
+    #[test]
+    fn whitespace_in_a_character_file_is_not_typed() {
+        override_letters("whitespace", "ab c\n");
+        assert_eq!(load_files_to_vec(letters()).unwrap(), vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn a_character_file_of_only_whitespace_is_empty() {
+        override_letters("blank", " \n\n");
+        assert!(matches!(load_files_to_vec(letters()), Err(AppError::EmptyResource(_))));
+    }
+}
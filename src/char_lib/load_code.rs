@@ -0,0 +1,52 @@
+use rand::seq::SliceRandom;
+
+use crate::char_lib::resources;
+use crate::error::AppError;
+use crate::settings::settings_struct::CodeLanguage;
+
+// line that separates two snippets in a code file
+static SNIPPET_SEPARATOR: &str = "%%";
+
+/// reads `code/<language>.txt`, where snippets are separated by a `%%` line
+pub fn load_snippets(language: CodeLanguage) -> Result<Vec<String>, AppError> {
+    let name = format!("code/{}.txt", language.file_name());
+    let s: String = resources::read_resource(&name).ok_or(AppError::MissingResource(name.clone()))?;
+    let mut snippets: Vec<String> = vec![];
+    let mut snippet: Vec<&str> = vec![];
+    for line in s.lines().chain([SNIPPET_SEPARATOR]) {
+        if line.trim_end() == SNIPPET_SEPARATOR {
+            if !snippet.is_empty() {
+                snippets.push(snippet.join("\n").trim_matches('\n').to_string());
+            }
+            snippet = vec![];
+        } else {
+            snippet.push(line.trim_end());
+        }
+    }
+    if snippets.is_empty() {
+        return Err(AppError::EmptyResource(name));
+    }
+    Ok(snippets)
+}
+
+pub fn chose_random_snippet(snippets: &[String]) -> Option<String> {
+    snippets.choose(&mut rand::thread_rng()).cloned()
+}
+
+/// the units of a snippet: every line without its indentation, ending with a
+/// newline that is typed with enter. The indentation is inserted automatically.
+pub fn snippet_lines(snippet: &str) -> Vec<String> {
+    let lines: Vec<&str> = snippet.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line.trim_start();
+            if i + 1 < lines.len() {
+                format!("{line}\n")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,9 @@
+
+use crate::char_lib::resources;
+use crate::error::AppError;
+use crate::settings::settings_struct::NgramList;
+
+pub fn load_ngram_list(list: NgramList) -> Result<Vec<String>, AppError> {
+    let name = format!("ngrams/{}.txt", list.file_name());
+    resources::read_lines(&name)
+}
@@ -0,0 +1,54 @@
+use rand::seq::SliceRandom;
+
+use crate::char_lib::{load_text, resources};
+use crate::error::AppError;
+use crate::settings::settings_struct::QuoteLength;
+
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub text: String,
+    pub author: String,
+    pub source: String,
+}
+
+impl Quote {
+    pub fn length(&self) -> QuoteLength {
+        QuoteLength::from_len(self.text.chars().count())
+    }
+
+    /// the words of the quote in the order they have to be typed
+    pub fn words(&self) -> Vec<String> {
+        load_text::spaced_words(&self.text)
+    }
+}
+
+/// reads `quotes.txt`, where every line is `author|source|text`
+pub fn load_quotes() -> Result<Vec<Quote>, AppError> {
+    let name = "quotes.txt".to_string();
+    let s: String = resources::read_resource(&name).ok_or(AppError::MissingResource(name.clone()))?;
+    let quotes: Vec<Quote> = s
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '|');
+            Some(Quote {
+                author: parts.next()?.trim().to_string(),
+                source: parts.next()?.trim().to_string(),
+                text: parts.next()?.trim().to_string(),
+            })
+        })
+        .collect();
+    if quotes.is_empty() {
+        return Err(AppError::EmptyResource(name));
+    }
+    Ok(quotes)
+}
+
+/// a quote of the given length, or of any length if there is none
+pub fn chose_random_quote(quotes: &[Quote], length: QuoteLength) -> Option<Quote> {
+    let bucket: Vec<&Quote> = quotes.iter().filter(|q| q.length() == length).collect();
+    match bucket.choose(&mut rand::thread_rng()) {
+        Some(quote) => Some((*quote).to_owned()),
+        None => quotes.choose(&mut rand::thread_rng()).cloned(),
+    }
+}
@@ -0,0 +1,179 @@
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::char_lib::layouts::KeyboardLayout;
+
+// a long text is split into sessions of about this many characters
+static SESSION_CHARS: usize = 600;
+
+// typographic characters and the plain characters they are typed as
+static REPLACEMENTS: [(char, &str); 11] = [
+    ('‘', "'"),
+    ('’', "'"),
+    ('‚', "'"),
+    ('“', "\""),
+    ('”', "\""),
+    ('„', "\""),
+    ('–', "-"),
+    ('—', "-"),
+    ('…', "..."),
+    ('«', "\""),
+    ('»', "\""),
+];
+
+/// a user provided text, split into sessions that are typed one after the other
+#[derive(Debug, Clone, Default)]
+pub struct CustomText {
+    // file name, or "stdin"
+    pub name: String,
+    pub sessions: Vec<String>,
+    // the session being typed
+    pub session: usize,
+    // characters that were removed because they cannot be typed
+    pub unsupported: Vec<char>,
+}
+
+impl CustomText {
+    pub fn new(name: &str, text: &str, layout: &KeyboardLayout) -> CustomText {
+        let (text, unsupported) = normalise(text, layout);
+        CustomText {
+            name: name.to_string(),
+            sessions: split_sessions(&text),
+            session: 0,
+            unsupported,
+        }
+    }
+
+    pub fn read_file(path: &Path, layout: &KeyboardLayout) -> io::Result<CustomText> {
+        let text = read_to_string(path)?;
+        let name = path
+            .file_name()
+            .map_or("text".to_string(), |name| name.to_string_lossy().to_string());
+        CustomText::from_text(&name, &text, layout)
+    }
+
+    pub fn read_stdin(layout: &KeyboardLayout) -> io::Result<CustomText> {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        CustomText::from_text("stdin", &text, layout)
+    }
+
+    /// text piped into the app without `--text -`, `None` when stdin is not a
+    /// pipe or a file or when nothing was piped in
+    pub fn read_piped_stdin(layout: &KeyboardLayout) -> io::Result<Option<CustomText>> {
+        if !stdin_is_piped() {
+            return Ok(None);
+        }
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        CustomText::from_text("stdin", &text, layout).map(Some)
+    }
+
+    fn from_text(name: &str, text: &str, layout: &KeyboardLayout) -> io::Result<CustomText> {
+        let custom = CustomText::new(name, text, layout);
+        if custom.sessions.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{name} contains no text that can be typed"),
+            ));
+        }
+        Ok(custom)
+    }
+
+    /// the words of the current session
+    pub fn words(&self) -> Vec<String> {
+        self.sessions
+            .get(self.session)
+            .map(|session| spaced_words(session))
+            .unwrap_or_default()
+    }
+
+    /// moves on to the next session, starting over after the last one
+    pub fn advance(&mut self) {
+        self.session = (self.session + 1) % self.sessions.len().max(1);
+    }
+}
+
+/// whether stdin is a pipe or a redirected file. A terminal, /dev/null or
+/// the socket a launcher passes in are left alone.
+#[cfg(unix)]
+fn stdin_is_piped() -> bool {
+    use std::fs::File;
+    use std::os::fd::AsFd;
+    use std::os::unix::fs::FileTypeExt;
+
+    io::stdin()
+        .as_fd()
+        .try_clone_to_owned()
+        .map(File::from)
+        .and_then(|file| file.metadata())
+        .is_ok_and(|meta| meta.file_type().is_fifo() || meta.is_file())
+}
+
+#[cfg(not(unix))]
+fn stdin_is_piped() -> bool {
+    use std::io::IsTerminal;
+
+    !io::stdin().is_terminal()
+}
+
+/// replaces typographic characters with plain ones, joins all whitespace into
+/// single spaces and removes characters that cannot be typed on the layout.
+/// Returns the text and the removed characters.
+pub fn normalise(text: &str, layout: &KeyboardLayout) -> (String, Vec<char>) {
+    let mut normalised = String::new();
+    let mut unsupported: Vec<char> = vec![];
+    for c in text.chars() {
+        if c.is_whitespace() {
+            normalised.push(' ');
+        } else if let Some((_, plain)) = REPLACEMENTS.iter().find(|(from, _)| *from == c) {
+            normalised.push_str(plain);
+        } else if c.is_ascii_graphic() || layout.key_position(c).is_some() {
+            normalised.push(c);
+        } else if !unsupported.contains(&c) {
+            unsupported.push(c);
+        }
+    }
+    let words: Vec<&str> = normalised.split_whitespace().collect();
+    (words.join(" "), unsupported)
+}
+
+/// the units of a text: every word with the space after it that is typed
+/// as well, the last word ends the text and has none
+pub fn spaced_words(text: &str) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            if i + 1 < words.len() {
+                format!("{word} ")
+            } else {
+                word.to_string()
+            }
+        })
+        .collect()
+}
+
+/// splits a text into sessions of about `SESSION_CHARS` characters, between words
+pub fn split_sessions(text: &str) -> Vec<String> {
+    let mut sessions: Vec<String> = vec![];
+    let mut session = String::new();
+    for word in text.split_whitespace() {
+        if !session.is_empty() && session.len() + word.len() >= SESSION_CHARS {
+            sessions.push(std::mem::take(&mut session));
+        }
+        if !session.is_empty() {
+            session.push(' ');
+        }
+        session.push_str(word);
+    }
+    if !session.is_empty() {
+        sessions.push(session);
+    }
+    sessions
+}
@@ -0,0 +1,14 @@
+use rand::seq::SliceRandom;
+
+use crate::char_lib::resources;
+use crate::error::AppError;
+use crate::settings::settings_struct::WordList;
+
+pub fn load_word_list(list: WordList) -> Result<Vec<String>, AppError> {
+    let name = format!("words/{}.txt", list.file_name());
+    resources::read_lines(&name)
+}
+
+pub fn chose_random_word(word_vec: &[String]) -> Option<String> {
+    word_vec.choose(&mut rand::thread_rng()).cloned()
+}
@@ -1,2 +1,12 @@
+pub mod adaptive;
+pub mod char_sets;
+pub mod fingers;
+pub mod layouts;
 pub mod load_chars;
+pub mod load_code;
+pub mod load_ngrams;
+pub mod load_quotes;
+pub mod load_text;
+pub mod load_words;
+pub mod resources;
 pub mod translator;
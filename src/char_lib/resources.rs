@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+
+use crate::error::AppError;
+
+static APP_NAME: &str = "crabtype";
+static CONFIG_NAME: &str = "config";
+
+// the default resources, compiled into the binary so it runs from any directory
+static EMBEDDED: [(&str, &str); 15] = [
+    ("letters.txt", include_str!("../../resources/letters.txt")),
+    ("cap_letters.txt", include_str!("../../resources/cap_letters.txt")),
+    ("numbers.txt", include_str!("../../resources/numbers.txt")),
+    ("parenthesis.txt", include_str!("../../resources/parenthesis.txt")),
+    ("special_characters.txt", include_str!("../../resources/special_characters.txt")),
+    ("quotes.txt", include_str!("../../resources/quotes.txt")),
+    ("words/top200.txt", include_str!("../../resources/words/top200.txt")),
+    ("words/top1k.txt", include_str!("../../resources/words/top1k.txt")),
+    ("words/top10k.txt", include_str!("../../resources/words/top10k.txt")),
+    ("ngrams/english.txt", include_str!("../../resources/ngrams/english.txt")),
+    ("ngrams/rust.txt", include_str!("../../resources/ngrams/rust.txt")),
+    ("code/rust.txt", include_str!("../../resources/code/rust.txt")),
+    ("code/python.txt", include_str!("../../resources/code/python.txt")),
+    ("code/c.txt", include_str!("../../resources/code/c.txt")),
+    ("code/shell.txt", include_str!("../../resources/code/shell.txt")),
+];
+
+thread_local! {
+    // the search path of tests, which don't see the user's directories
+    pub static TEST_SEARCH_PATH: RefCell<Vec<PathBuf>> = const { RefCell::new(vec![]) };
+}
+
+/// the directories searched for resource files before the embedded defaults,
+/// in order: the data dir (e.g. ~/.local/share/crabtype) and the config dir
+pub fn search_path() -> Vec<PathBuf> {
+    if cfg!(test) {
+        return TEST_SEARCH_PATH.with(|dirs| dirs.borrow().clone());
+    }
+    let mut dirs: Vec<PathBuf> = vec![];
+    if let Some(project) = ProjectDirs::from("rs", "", APP_NAME) {
+        dirs.push(project.data_dir().to_path_buf());
+    }
+    if let Some(dir) = confy::get_configuration_file_path(APP_NAME, CONFIG_NAME)
+        .ok()
+        .and_then(|path| path.parent().map(|dir| dir.to_path_buf()))
+    {
+        // both are the same directory on some platforms
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// a resource like `words/top200.txt`, from the first directory in the search
+/// path that has it, or else the embedded default
+pub fn read_resource(name: &str) -> Option<String> {
+    search_path()
+        .iter()
+        .find_map(|dir| fs::read_to_string(dir.join(name)).ok())
+        .or_else(|| {
+            EMBEDDED
+                .iter()
+                .find(|(embedded, _)| *embedded == name)
+                .map(|(_, contents)| contents.to_string())
+        })
+}
+
+/// the trimmed, non-empty lines of a resource, it is an error if there are none
+pub fn read_lines(name: &str) -> Result<Vec<String>, AppError> {
+    let s = read_resource(name).ok_or_else(|| AppError::MissingResource(name.to_string()))?;
+    let lines: Vec<String> = s
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return Err(AppError::EmptyResource(name.to_string()));
+    }
+    Ok(lines)
+}
+
+/// name (without extension) and contents of every file with `extension` users
+/// put in `dir` of the search path. A file in an earlier directory hides one with the
+/// same name in a later directory.
+pub fn user_resources(dir: &str, extension: &str) -> Vec<(String, String)> {
+    let mut resources: Vec<(String, String)> = vec![];
+    for search_dir in search_path() {
+        let Ok(entries) = fs::read_dir(search_dir.join(dir)) else {
+            continue;
+        };
+        let mut found: Vec<(String, String)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().to_string();
+                Some((name, fs::read_to_string(&path).ok()?))
+            })
+            .filter(|(name, _)| !resources.iter().any(|(existing, _)| existing == name))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        resources.extend(found);
+    }
+    resources
+}
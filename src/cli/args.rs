@@ -0,0 +1,235 @@
+use std::path::PathBuf;
+
+use clap::builder::BoolishValueParser;
+use clap::{Args, Parser, Subcommand};
+
+use crate::char_lib::char_sets::custom_char_sets;
+use crate::game::game_page::get_dict;
+use crate::settings::settings_struct::{
+    CodeLanguage, GameMode, NgramList, QuoteLength, Settings, WordList,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "crabtype", version, about = "A typing trainer for the terminal")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// open the menu with these overrides
+    #[command(flatten)]
+    pub play: PlayArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// start a game right away
+    Play(PlayArgs),
+    /// show the statistics page
+    Stats,
+    /// read or change the stored settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// print the progress of the lesson curriculum
+    Lessons,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// print a setting, or every setting when no key is given
+    Get { key: Option<String> },
+    /// store a new value for a setting
+    Set { key: String, value: String },
+    /// print where the config file is stored and where resource files are searched
+    Path,
+}
+
+/// settings that can be overridden for a single run, the stored config is not changed
+#[derive(Debug, Clone, Default, Args)]
+pub struct PlayArgs {
+    /// game mode: characters, words, quote, lessons, ngrams or code
+    #[arg(long, value_parser = parse_mode)]
+    pub mode: Option<GameMode>,
+    /// length of a timed game in seconds
+    #[arg(long)]
+    pub time: Option<u8>,
+    /// character sets, e.g. letters,numbers
+    #[arg(long, value_delimiter = ',', value_parser = parse_set)]
+    pub sets: Option<Vec<String>>,
+    /// word list: top200, top1k or top10k
+    #[arg(long, value_parser = parse_word_list)]
+    pub words: Option<WordList>,
+    /// quote length: short, medium or long
+    #[arg(long, value_parser = parse_quote_length)]
+    pub quote_length: Option<QuoteLength>,
+    /// n-gram list: english or rust
+    #[arg(long, value_parser = parse_ngram_list)]
+    pub ngrams: Option<NgramList>,
+    /// snippet language: rust, python, c or shell
+    #[arg(long, value_parser = parse_language)]
+    pub language: Option<CodeLanguage>,
+    /// keyboard layout, e.g. Colemak
+    #[arg(long)]
+    pub layout: Option<String>,
+    /// the OS layout to translate key presses from when practicing `--layout`
+    #[arg(long)]
+    pub emulate_from: Option<String>,
+    /// survival mode with a rising speed limit, `--hardcore=off` turns it off
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "on")]
+    #[arg(value_name = "ON|OFF", value_parser = BoolishValueParser::new())]
+    #[arg(hide_possible_values = true)]
+    pub hardcore: Option<bool>,
+    /// practice the keys with the most mistakes more often
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "on")]
+    #[arg(value_name = "ON|OFF", value_parser = BoolishValueParser::new())]
+    #[arg(hide_possible_values = true)]
+    pub adaptive: Option<bool>,
+    /// show which finger types the next key
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "on")]
+    #[arg(value_name = "ON|OFF", value_parser = BoolishValueParser::new())]
+    #[arg(hide_possible_values = true)]
+    pub ten_finger: Option<bool>,
+    /// practice on a text file, `-` reads stdin
+    #[arg(long, conflicts_with = "book")]
+    pub text: Option<PathBuf>,
+    /// put a file on the bookshelf, it can be resumed from the menu
+    #[arg(long)]
+    pub book: Option<PathBuf>,
+}
+
+impl PlayArgs {
+    /// overrides the values in `settings` that were given on the command line
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(mode) = self.mode {
+            settings.game_mode = mode;
+        }
+        if let Some(time) = self.time {
+            settings.total_time_sec = time;
+        }
+        if let Some(sets) = &self.sets {
+            settings.lower_case_letters = sets.iter().any(|s| s == "letters");
+            settings.capital_letters = sets.iter().any(|s| s == "cap_letters");
+            settings.numbers = sets.iter().any(|s| s == "numbers");
+            settings.parenthesis = sets.iter().any(|s| s == "parenthesis");
+            settings.special_characters = sets.iter().any(|s| s == "special_characters");
+            settings.custom_sets = custom_char_sets()
+                .into_iter()
+                .map(|set| set.id)
+                .filter(|id| sets.contains(id))
+                .collect();
+        }
+        if let Some(words) = self.words {
+            settings.word_list = words;
+        }
+        if let Some(quote_length) = self.quote_length {
+            settings.quote_length = quote_length;
+        }
+        if let Some(ngrams) = self.ngrams {
+            settings.ngram_list = ngrams;
+        }
+        if let Some(language) = self.language {
+            settings.code_language = language;
+        }
+        if let Some(layout) = &self.layout {
+            settings.layout = layout.clone();
+        }
+        if let Some(emulate_from) = &self.emulate_from {
+            settings.emulate_from = Some(emulate_from.clone());
+        }
+        if let Some(hardcore) = self.hardcore {
+            settings.hardcore = hardcore;
+        }
+        if let Some(adaptive) = self.adaptive {
+            settings.adaptive = adaptive;
+        }
+        if let Some(ten_finger) = self.ten_finger {
+            settings.ten_finger_typing = ten_finger;
+        }
+    }
+}
+
+/// a built in or custom character set
+fn parse_set(value: &str) -> Result<String, String> {
+    let known: Vec<String> = get_dict(&Settings::default())
+        .into_iter()
+        .map(|entry| entry.key)
+        .chain(custom_char_sets().into_iter().map(|set| set.id))
+        .collect();
+    if known.iter().any(|set| set == value) {
+        Ok(value.to_string())
+    } else {
+        Err(format!("expected one of: {}", known.join(", ")))
+    }
+}
+
+/// "Top 1k" and "N-grams" are written as top1k and ngrams on the command line
+fn simplify(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn parse_choice<T: Copy>(value: &str, options: &[T], name: fn(&T) -> &'static str) -> Result<T, String> {
+    options
+        .iter()
+        .find(|option| simplify(name(option)) == simplify(value))
+        .copied()
+        .ok_or_else(|| {
+            let names: Vec<String> = options.iter().map(|o| simplify(name(o))).collect();
+            format!("expected one of: {}", names.join(", "))
+        })
+}
+
+pub fn parse_mode(value: &str) -> Result<GameMode, String> {
+    parse_choice(value, &GameMode::SELECTABLE, GameMode::name)
+}
+
+pub fn parse_word_list(value: &str) -> Result<WordList, String> {
+    parse_choice(value, &WordList::ALL, WordList::name)
+}
+
+pub fn parse_quote_length(value: &str) -> Result<QuoteLength, String> {
+    parse_choice(value, &QuoteLength::ALL, QuoteLength::name)
+}
+
+pub fn parse_ngram_list(value: &str) -> Result<NgramList, String> {
+    parse_choice(value, &NgramList::ALL, NgramList::name)
+}
+
+pub fn parse_language(value: &str) -> Result<CodeLanguage, String> {
+    parse_choice(value, &CodeLanguage::ALL, CodeLanguage::name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_args(args: &[&str]) -> PlayArgs {
+        let cli = Cli::try_parse_from(["crabtype", "play"].iter().chain(args)).unwrap();
+        match cli.command {
+            Some(Command::Play(args)) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn switches_override_the_stored_settings_both_ways() {
+        let mut settings = Settings {
+            hardcore: true,
+            adaptive: false,
+            ten_finger_typing: true,
+            ..Settings::default()
+        };
+        play_args(&["--hardcore=off", "--adaptive"]).apply(&mut settings);
+        assert!(!settings.hardcore);
+        assert!(settings.adaptive);
+        assert!(settings.ten_finger_typing);
+    }
+
+    #[test]
+    fn unknown_character_sets_are_rejected() {
+        assert!(Cli::try_parse_from(["crabtype", "play", "--sets", "letters,bogus"]).is_err());
+    }
+}
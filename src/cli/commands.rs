@@ -0,0 +1,186 @@
+use std::io;
+
+use crate::books::bookshelf::{Book, Bookshelf};
+use crate::char_lib::{layouts, resources};
+use crate::char_lib::load_text::CustomText;
+use crate::cli::args::{self, ConfigAction, PlayArgs};
+use crate::error::AppError;
+use crate::lessons::lesson_progress::{self, LessonProgress};
+use crate::settings::settings_struct::Settings;
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// puts the file of `--book <file>` on the bookshelf and returns it with its bookmark
+pub fn shelve_book(args: &PlayArgs, settings: &Settings) -> io::Result<Option<Book>> {
+    let Some(path) = &args.book else {
+        return Ok(None);
+    };
+    let layout = layouts::load_layout(&settings.layout);
+    let mut shelf =
+        Bookshelf::read().map_err(|e| io::Error::other(AppError::BadSaveFile("bookshelf", e)))?;
+    let book = shelf.add(path, &layout)?;
+    shelf.write().map_err(io::Error::other)?;
+    Ok(Some(book))
+}
+
+/// the text to practice on from `--text <file>`, `--text -` or piped stdin,
+/// an empty pipe plays the configured mode
+pub fn custom_text(args: &PlayArgs, settings: &Settings) -> io::Result<Option<CustomText>> {
+    let layout = layouts::load_layout(&settings.layout);
+    match &args.text {
+        Some(path) if path.as_os_str() == "-" => CustomText::read_stdin(&layout).map(Some),
+        Some(path) => CustomText::read_file(path, &layout).map(Some),
+        None => CustomText::read_piped_stdin(&layout),
+    }
+}
+
+/// settings as a toml table, with every optional setting present
+/// so the keys and their types can be looked up
+fn settings_table(settings: &Settings) -> io::Result<toml::Table> {
+    toml::Table::try_from(settings).map_err(io::Error::other)
+}
+
+fn template_table() -> io::Result<toml::Table> {
+    settings_table(&Settings {
+        emulate_from: Some(String::new()),
+        ..Settings::default()
+    })
+}
+
+fn format_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// a choice or layout setting, parsed the same way as its command line flag
+fn choice_value(key: &str, value: &str) -> Result<toml::Value, String> {
+    fn to_toml<T: serde::Serialize>(choice: T) -> Result<toml::Value, String> {
+        toml::Value::try_from(choice).map_err(|e| e.to_string())
+    }
+    match key {
+        "game_mode" => args::parse_mode(value).and_then(to_toml),
+        "word_list" => args::parse_word_list(value).and_then(to_toml),
+        "quote_length" => args::parse_quote_length(value).and_then(to_toml),
+        "ngram_list" => args::parse_ngram_list(value).and_then(to_toml),
+        "code_language" => args::parse_language(value).and_then(to_toml),
+        "layout" | "emulate_from" => layouts::find_layout(value)
+            .map(|layout| toml::Value::String(layout.name))
+            .map_err(|e| e.to_string()),
+        _ => Ok(toml::Value::String(value.to_string())),
+    }
+}
+
+pub fn config(action: ConfigAction) -> io::Result<()> {
+    let settings = Settings::read_config().map_err(io::Error::other)?;
+    let template = template_table()?;
+    match action {
+        ConfigAction::Get { key: None } => {
+            for (key, value) in settings_table(&settings)? {
+                println!("{key} = {}", format_value(&value));
+            }
+        }
+        ConfigAction::Get { key: Some(key) } => {
+            if !template.contains_key(&key) {
+                return Err(invalid_input(format!("unknown setting '{key}'")));
+            }
+            match settings_table(&settings)?.get(&key) {
+                Some(value) => println!("{}", format_value(value)),
+                None => println!("off"),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let mut table = settings_table(&settings)?;
+            let parsed = match template.get(&key) {
+                None => return Err(invalid_input(format!("unknown setting '{key}'"))),
+                Some(toml::Value::Boolean(_)) => match value.as_str() {
+                    "on" | "true" => Some(toml::Value::Boolean(true)),
+                    "off" | "false" => Some(toml::Value::Boolean(false)),
+                    _ => return Err(invalid_input(format!("{key} is either on or off"))),
+                },
+                Some(toml::Value::Integer(_)) => {
+                    let range = Settings::number_range(&key);
+                    match value.parse::<u16>() {
+                        Ok(number) if range.contains(&number) => {
+                            Some(toml::Value::Integer(number.into()))
+                        }
+                        _ => {
+                            return Err(invalid_input(format!(
+                                "{key} is a number from {} to {}",
+                                range.start(),
+                                range.end()
+                            )))
+                        }
+                    }
+                }
+                // an optional setting is turned off by setting it to "off"
+                Some(_) if key == "emulate_from" && value == "off" => None,
+                Some(_) => Some(
+                    choice_value(&key, &value)
+                        .map_err(|e| invalid_input(format!("invalid value for {key}: {e}")))?,
+                ),
+            };
+            match parsed {
+                Some(parsed) => table.insert(key.clone(), parsed),
+                None => table.remove(&key),
+            };
+            let new_settings: Settings = toml::Value::Table(table)
+                .try_into()
+                .map_err(|e| invalid_input(format!("invalid value for {key}: {e}")))?;
+            Settings::write_config(&new_settings).map_err(io::Error::other)?;
+        }
+        ConfigAction::Path => {
+            let path = confy::get_configuration_file_path("crabtype", "config")
+                .map_err(io::Error::other)?;
+            println!("config: {}", path.display());
+            for dir in resources::search_path() {
+                println!("resources: {}", dir.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// prints the unlocked keys with their speed and accuracy, and the keys still locked
+pub fn lessons() -> io::Result<()> {
+    let settings = Settings::read_config().map_err(io::Error::other)?;
+    let layout = layouts::load_layout(&settings.layout);
+    let progress = LessonProgress::read().map_err(io::Error::other)?;
+    let order = lesson_progress::lesson_order(&layout);
+
+    println!(
+        "{} lessons: {}/{} keys unlocked, target {} char/min at {}% accuracy",
+        layout.name,
+        progress.unlocked.min(order.len()),
+        order.len(),
+        settings.lesson_target_cpm,
+        settings.lesson_target_accuracy
+    );
+    println!();
+    println!("key  char/min  accuracy  progress");
+    for key in progress.unlocked_keys(&layout) {
+        let done = progress.key_progress(
+            key,
+            settings.lesson_target_cpm,
+            settings.lesson_target_accuracy,
+        );
+        match progress.key(key) {
+            Some(stats) => println!(
+                "{key:<4} {:>8.0} {:>8.1}% {:>8.0}%",
+                stats.cpm,
+                stats.accuracy,
+                100.0 * done
+            ),
+            None => println!("{key:<4} {:>8} {:>9} {:>8.0}%", "-", "-", 100.0 * done),
+        }
+    }
+    let locked: String = order.iter().skip(progress.unlocked).collect();
+    if !locked.is_empty() {
+        println!();
+        println!("locked: {locked}");
+    }
+    Ok(())
+}
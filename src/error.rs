@@ -0,0 +1,43 @@
+use std::io;
+
+use thiserror::Error;
+
+/// problems the app can recover from, shown on the notice page
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("the config file could not be read")]
+    BadConfig(#[from] confy::ConfyError),
+    #[error("the resource {0} could not be found")]
+    MissingResource(String),
+    #[error("there is nothing to type in {0}")]
+    EmptyResource(String),
+    #[error("the keyboard layout {0} could not be found")]
+    MissingLayout(String),
+    #[error("the {0} could not be read")]
+    BadSaveFile(&'static str, #[source] confy::ConfyError),
+    #[error("the {0} could not be saved")]
+    SaveFailed(&'static str, #[source] io::Error),
+}
+
+impl AppError {
+    /// what the user can do about the problem
+    pub fn hint(&self) -> &'static str {
+        match self {
+            AppError::BadConfig(_) => {
+                "The default settings are used. Saving from the settings page overwrites the file."
+            }
+            AppError::MissingResource(_) | AppError::EmptyResource(_) => {
+                "Check the resource directories listed by `crabtype config path`."
+            }
+            AppError::MissingLayout(_) => {
+                "Pick another layout in the settings or add it to a `layouts` resource directory."
+            }
+            AppError::BadSaveFile(..) => {
+                "The file is not written until it can be read, fix or remove it to play on."
+            }
+            AppError::SaveFailed(..) => {
+                "Check that the data and config directories can be written."
+            }
+        }
+    }
+}
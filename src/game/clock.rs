@@ -0,0 +1,89 @@
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::Instant;
+
+use chrono::{Duration, TimeDelta};
+
+/// where a game gets its time from
+pub trait Clock: Debug {
+    /// time since the clock was created, it never goes backwards
+    fn now(&self) -> Duration;
+}
+
+/// the system's monotonic clock, unaffected by changes to the wall clock
+/// like daylight saving time or NTP adjustments
+#[derive(Debug)]
+pub struct MonotonicClock {
+    created: Instant,
+}
+
+impl Default for MonotonicClock {
+    fn default() -> MonotonicClock {
+        MonotonicClock {
+            created: Instant::now(),
+        }
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Duration {
+        Duration::from_std(self.created.elapsed()).unwrap_or(TimeDelta::MAX)
+    }
+}
+
+/// a clock that only moves when it is advanced, clones share the same time
+/// so a game can be driven from outside with exact keystroke timings
+#[derive(Debug, Clone, Default)]
+pub struct FakeClock {
+    time: Rc<Cell<Duration>>,
+}
+
+impl FakeClock {
+    /// moves the clock forward by `by`
+    #[cfg(test)]
+    pub fn advance(&self, by: Duration) {
+        self.time.set(self.time.get() + by.max(Duration::zero()));
+    }
+
+    /// moves the clock to `time`, or leaves it if it is already past it
+    pub fn set(&self, time: Duration) {
+        self.time.set(self.time.get().max(time));
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.time.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_clock_clones_share_the_time() {
+        let clock = FakeClock::default();
+        let game_clock = clock.clone();
+        clock.advance(Duration::milliseconds(1500));
+        assert_eq!(game_clock.now(), Duration::milliseconds(1500));
+    }
+
+    #[test]
+    fn fake_clock_never_goes_backwards() {
+        let clock = FakeClock::default();
+        clock.set(Duration::seconds(5));
+        clock.set(Duration::seconds(2));
+        clock.advance(Duration::seconds(-1));
+        assert_eq!(clock.now(), Duration::seconds(5));
+    }
+
+    #[test]
+    fn monotonic_clock_counts_up() {
+        let clock = MonotonicClock::default();
+        let first = clock.now();
+        assert!(first >= Duration::zero());
+        assert!(clock.now() >= first);
+    }
+}
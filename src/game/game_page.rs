@@ -1,9 +1,27 @@
+use std::io;
 use std::rc::Rc;
 
-use crate::char_lib::{load_chars, translator};
-use crate::settings::settings_struct::Settings;
+use crate::char_lib::load_quotes::{self, Quote};
+use crate::char_lib::load_text::{self, CustomText};
+use crate::books::bookshelf::{Book, Bookshelf};
+use crate::char_lib::char_sets::{self, CharSet};
+use crate::char_lib::layouts::{self, KeyboardLayout};
+use crate::char_lib::{
+    adaptive, fingers, load_chars, load_code, load_ngrams, load_words, translator,
+};
+use crate::error::AppError;
+use crate::game::clock::Clock;
+use crate::game::ghost::Ghost;
+use crate::game::highlight;
+use crate::game::metrics::{self, Keystroke, Metrics, NgramResult};
+use crate::history::session_history::{self, SessionRecord, HISTORY_VERSION};
+use crate::lessons::lesson_progress::{self, LessonProgress};
+use crate::settings::settings_struct::{GameMode, Settings};
+use crate::stats::keyboard::{FingerKeyboard, HeatmapMetric, KeyboardHeatmap};
+use crate::tui::screen::{Action, Page};
 
-use chrono::{DateTime, Duration, Local};
+use chrono::{Duration, Local};
+use crossterm::event::{KeyCode, KeyEvent};
 use dict::{Dict, DictIface};
 
 use ratatui::{
@@ -13,7 +31,7 @@ use ratatui::{
     widgets::{block::*, *},
 };
 
-pub fn get_dict(set: Settings) -> Dict<bool> {
+pub fn get_dict(set: &Settings) -> Dict<bool> {
     let mut dict: Dict<bool> = Dict::<bool>::new();
     dict.add("letters".to_string(), set.lower_case_letters);
     dict.add("cap_letters".to_string(), set.capital_letters);
@@ -23,66 +41,222 @@ pub fn get_dict(set: Settings) -> Dict<bool> {
     dict
 }
 
+// the newest key of a lesson is drawn this many times as often as the others
+static LESSON_FOCUS_WEIGHT: f64 = 3.0;
+
+// characters per minute in hardcore mode are measured over this many seconds,
+// and the speed limit is only enforced once the first window has passed
+static HARDCORE_WINDOW_SEC: i64 = 10;
+
+/// why a game ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEnd {
+    TimeUp,
+    TextFinished,
+    TooSlow,
+    TooManyMistakes,
+}
+
 #[derive(Debug)]
 pub struct GameLogic {
     pub time: Duration,
-    pub start_time: DateTime<Local>,
-    pub random_char: char,
+    // reading of `clock` when the game started
+    pub start_time: Duration,
+    pub clock: Box<dyn Clock>,
+    // the unit being typed: a single character or a whole word
+    pub target: String,
+    // number of characters of `target` typed correctly so far
+    pub target_pos: usize,
+    pub target_error: bool,
     pub char_vec: Vec<char>,
-    pub score: u32,
+    // weights of `char_vec` when adaptive practice has history to work with
+    pub char_weights: Option<Vec<f64>>,
+    // weights of `char_vec` given by the character set files, 1.0 for built in sets
+    pub base_weights: Vec<f64>,
+    // custom character sets that are played
+    pub custom_sets: Vec<CharSet>,
+    pub word_vec: Vec<String>,
+    pub quotes: Vec<Quote>,
+    // the quote being typed in quote mode and its words not yet queued
+    pub quote: Option<Quote>,
+    pub passage: Vec<String>,
+    // the snippet being typed in code mode, one unit per line
+    pub snippets: Vec<String>,
+    pub snippet: Option<String>,
+    // units typed to the end, the index of the unit being typed
+    pub units_done: usize,
+    // the text typed in text mode
+    pub text: Option<CustomText>,
+    // the book typed in book mode and the passage being typed
+    pub book: Option<Book>,
+    pub book_passage: String,
+    // the units of a recorded session when it is replayed, nothing is saved then
+    pub replay: Option<Vec<String>>,
+    // whether earlier sessions are read and finished games are saved to the
+    // history, the lesson progress and the bookshelf
+    pub persist: bool,
+    // the session history, read when the first game starts and kept across restarts
+    pub history: Option<Vec<SessionRecord>>,
+    // problems saving the last game, for the notice page
+    pub errors: Vec<AppError>,
+    pub ghost: Option<Ghost>,
+    // curriculum progress in lesson mode, and the key unlocked by the last game
+    pub lesson: Option<LessonProgress>,
+    pub unlocked_key: Option<char>,
     pub play: bool,
     pub hist_amount: u8,
     pub future_amount: u8,
-    pub char_hist: Vec<char>,
-    pub char_future: Vec<char>,
+    pub char_hist: Vec<String>,
+    pub char_future: Vec<String>,
     pub correct_hist: Vec<bool>,
+    // every key pressed in the session, in order
+    pub keystrokes: Vec<Keystroke>,
+    pub peak_cpm: f64,
+    pub end: Option<GameEnd>,
+    pub layout: KeyboardLayout,
+    // the OS layout key presses are translated from when emulating `layout`
+    pub os_layout: Option<KeyboardLayout>,
     pub settings: Settings,
 }
 
-impl Default for GameLogic {
-    fn default() -> GameLogic {
-        let mut loaded_settings = Settings::read_config().unwrap();
-        if !loaded_settings.lower_case_letters && !loaded_settings.capital_letters && !loaded_settings.numbers && !loaded_settings.parenthesis && !loaded_settings.special_characters {
+impl GameLogic {
+    /// a game with the given settings timed by `clock`, it is an error if one of
+    /// the files the game mode draws from is missing or leaves nothing to type
+    pub fn new(
+        mut loaded_settings: Settings,
+        clock: Box<dyn Clock>,
+    ) -> Result<GameLogic, AppError> {
+        let custom_sets: Vec<CharSet> = char_sets::custom_char_sets()
+            .into_iter()
+            .filter(|set| loaded_settings.custom_sets.contains(&set.id))
+            .collect();
+        if !loaded_settings.lower_case_letters && !loaded_settings.capital_letters && !loaded_settings.numbers && !loaded_settings.parenthesis && !loaded_settings.special_characters && custom_sets.is_empty() {
             loaded_settings.lower_case_letters = true
         }
-        let dict: Dict<bool> = get_dict(loaded_settings);
-        let start_t = Local::now();
-        let load_char: Vec<char> = load_chars::load_files_to_vec(dict);
-        let mut h_vec = vec![];
-        let mut f_vec = vec![];
-        let mut c_hist = vec![];
-        let h_amount: u8 = loaded_settings.history_length;
-        let f_amount: u8 = loaded_settings.future_length;
-        for _ in 0..h_amount {
-            Vec::push(&mut h_vec, ' ');
-            Vec::push(&mut c_hist, false);
-        }
-        for _ in 0..f_amount {
-            f_vec.push(load_chars::chose_random(load_char.to_owned()));
-        }
-
-        GameLogic {
-            time: Local::now().signed_duration_since(start_t),
-            start_time: start_t,
-            random_char: load_chars::chose_random(load_char.to_owned()),
-            char_vec: load_char.to_owned(),
-            score: 0,
+        let dict: Dict<bool> = get_dict(&loaded_settings);
+        let layout = layouts::find_layout(&loaded_settings.layout)?;
+        let os_layout = match loaded_settings.emulate_from.as_deref() {
+            Some(name) => Some(layouts::find_layout(name)?).filter(|os| *os != layout),
+            None => None,
+        };
+        let lesson: Option<LessonProgress> = match loaded_settings.game_mode {
+            GameMode::Lesson => Some(
+                LessonProgress::read().map_err(|e| AppError::BadSaveFile("lesson progress", e))?,
+            ),
+            _ => None,
+        };
+        let mut load_char: Vec<char> = match &lesson {
+            Some(progress) => progress.unlocked_keys(&layout),
+            None => load_chars::load_files_to_vec(dict)?,
+        };
+        let mut base_weights: Vec<f64> = vec![1.0; load_char.len()];
+        if lesson.is_none() {
+            for set in &custom_sets {
+                load_char.extend(&set.chars);
+                base_weights.extend(&set.weights);
+            }
+        }
+        if loaded_settings.game_mode.is_character_based() && load_char.is_empty() {
+            return Err(AppError::EmptyResource("the selected character sets".to_string()));
+        }
+        let load_word: Vec<String> = match loaded_settings.game_mode {
+            GameMode::Words => load_words::load_word_list(loaded_settings.word_list)?,
+            GameMode::Ngrams => load_ngrams::load_ngram_list(loaded_settings.ngram_list)?,
+            _ => vec![],
+        };
+        let load_quote: Vec<Quote> = match loaded_settings.game_mode {
+            GameMode::Quote => load_quotes::load_quotes()?,
+            _ => vec![],
+        };
+
+        let load_snippet: Vec<String> = match loaded_settings.game_mode {
+            GameMode::Code => load_code::load_snippets(loaded_settings.code_language)?,
+            _ => vec![],
+        };
+
+        let mut game = GameLogic {
+            time: Duration::zero(),
+            start_time: clock.now(),
+            clock,
+            target: String::new(),
+            target_pos: 0,
+            target_error: false,
+            char_vec: load_char,
+            char_weights: None,
+            base_weights,
+            custom_sets,
+            word_vec: load_word,
+            quotes: load_quote,
+            quote: None,
+            passage: vec![],
+            snippets: load_snippet,
+            snippet: None,
+            units_done: 0,
+            text: None,
+            book: None,
+            book_passage: String::new(),
+            replay: None,
+            persist: true,
+            history: None,
+            errors: vec![],
+            ghost: None,
+            lesson,
+            unlocked_key: None,
             play: true,
-            hist_amount: h_amount,
-            future_amount: f_amount,
-            char_hist: h_vec,
-            char_future: f_vec,
-            correct_hist: c_hist,
+            hist_amount: loaded_settings.history_length,
+            future_amount: loaded_settings.future_length,
+            char_hist: vec![],
+            char_future: vec![],
+            correct_hist: vec![],
+            keystrokes: vec![],
+            peak_cpm: 0.0,
+            end: None,
+            layout,
+            os_layout,
             settings: loaded_settings,
-        }
+        };
+        game.reset_char_vec();
+        Ok(game)
     }
-}
 
-impl GameLogic {
     pub fn get_time(&mut self) {
-        let time_now = Local::now();
-        self.time = time_now.signed_duration_since(self.start_time);
+        // the clock stops with the game, so text based modes keep their finish time
+        if !self.play {
+            return;
+        }
+        self.time = self.clock.now() - self.start_time;
+    }
+    /// draws the next unit to type from the configured game mode,
+    /// returns `None` once a text based mode has run out of text
+    pub fn next_unit(&mut self) -> Option<String> {
+        if self.replay.is_some() {
+            return (!self.passage.is_empty()).then(|| self.passage.remove(0));
+        }
+        match self.settings.game_mode {
+            GameMode::Characters | GameMode::Lesson => match &self.char_weights {
+                Some(weights) => load_chars::chose_weighted(&self.char_vec, weights),
+                None => load_chars::chose_random(&self.char_vec),
+            }
+            .map(|c| c.to_string()),
+            // words are typed with the space after them
+            GameMode::Words | GameMode::Ngrams => {
+                load_words::chose_random_word(&self.word_vec).map(|word| format!("{word} "))
+            }
+            GameMode::Quote | GameMode::Code | GameMode::Text | GameMode::Book => {
+                if self.passage.is_empty() {
+                    None
+                } else {
+                    Some(self.passage.remove(0))
+                }
+            }
+        }
     }
+
+    /// the character the player is expected to press next
+    pub fn expected_char(&self) -> char {
+        self.target.chars().nth(self.target_pos).unwrap_or(' ')
+    }
+
     pub fn reset_char_vec(&mut self) {
         self.char_future = vec![];
         self.char_hist = vec![];
@@ -100,43 +274,417 @@ impl GameLogic {
         };
 
         for _ in 0..hist_loop {
-            self.char_hist.push(' ');
+            self.char_hist.push(" ".to_string());
             self.correct_hist.push(false);
         }
+        if self.settings.game_mode == GameMode::Quote {
+            self.quote = load_quotes::chose_random_quote(&self.quotes, self.settings.quote_length);
+            self.passage = self.quote.as_ref().map(|q| q.words()).unwrap_or_default();
+        }
+        if self.settings.game_mode == GameMode::Code {
+            self.snippet = load_code::chose_random_snippet(&self.snippets);
+            self.passage = self
+                .snippet
+                .as_deref()
+                .map(load_code::snippet_lines)
+                .unwrap_or_default();
+        }
+        if let Some(text) = &self.text {
+            self.passage = text.words();
+        }
+        if self.book.is_some() {
+            self.passage = load_text::spaced_words(&self.book_passage);
+        }
+        if let Some(units) = &self.replay {
+            self.passage = units.clone();
+            self.quote = None;
+            // code is shown without its indentation, which isn't typed
+            self.snippet = (self.settings.game_mode == GameMode::Code).then(|| units.concat());
+        }
+        self.units_done = 0;
+        self.target = self.next_unit().unwrap_or_default();
         for _ in 0..future_loop {
-            self.char_future
-                .push(load_chars::chose_random(self.char_vec.to_owned()));
+            if let Some(unit) = self.next_unit() {
+                self.char_future.push(unit);
+            }
+        }
+        self.target_pos = 0;
+        self.target_error = false;
+    }
+    /// plays `text` in text mode, one session of it per game
+    pub fn use_text(&mut self, text: CustomText) {
+        self.settings.game_mode = GameMode::Text;
+        self.lesson = None;
+        self.text = Some(text);
+    }
+
+    /// plays the units of a recorded session again, the keys are fed in by the caller
+    pub fn use_replay(&mut self, units: Vec<String>) {
+        self.lesson = None;
+        self.replay = Some(units);
+        self.persist = false;
+    }
+
+    /// plays `book` from its bookmark in book mode
+    pub fn use_book(&mut self, book: Book) {
+        self.settings.game_mode = GameMode::Book;
+        self.lesson = None;
+        self.book = Some(book);
+    }
+
+    /// the text to play next: the next session once the current one is finished
+    pub fn remaining_text(&self) -> Option<CustomText> {
+        let mut text = self.text.clone()?;
+        if self.end == Some(GameEnd::TextFinished) {
+            text.advance();
         }
+        Some(text)
     }
-    pub fn reset(&mut self) {
-        self.start_time = Local::now();
-        self.score = 0;
+
+    /// starts the game over, it is an error if the book being typed can't be
+    /// read, the game is left as it was then
+    pub fn reset(&mut self) -> Result<(), AppError> {
+        if let Some(book) = &self.book {
+            self.book_passage = book
+                .next_passage(&self.layout)
+                .map_err(|_| AppError::MissingResource(book.path.clone()))?;
+        }
+        self.text = self.remaining_text();
+        self.start_time = self.clock.now();
+        self.time = Duration::zero();
+        self.keystrokes = vec![];
+        self.peak_cpm = 0.0;
+        self.end = None;
         self.play = true;
+        self.unlocked_key = None;
+        if let Some(progress) = &self.lesson {
+            self.char_vec = progress.unlocked_keys(&self.layout);
+            self.base_weights = vec![1.0; self.char_vec.len()];
+        }
+        if self.persist && self.history.is_none() {
+            self.history = Some(session_history::load_sessions());
+        }
+        self.update_char_weights();
         self.reset_char_vec();
+        self.ghost = self.find_ghost();
+        Ok(())
+    }
+
+    /// whether the book being typed has been typed to the end
+    pub fn book_finished(&self) -> bool {
+        self.book.as_ref().is_some_and(Book::is_finished)
+    }
+
+    /// starts the game over, a finished book goes back to the menu instead
+    fn restart(&mut self) -> Action {
+        if self.book_finished() {
+            return Action::Pop;
+        }
+        match self.reset() {
+            Ok(()) => Action::None,
+            Err(error) => Action::Notify(error),
+        }
+    }
+
+    /// the best earlier run to race against: of the same text in text based
+    /// modes, with the same character sets and time in timed modes
+    pub fn find_ghost(&self) -> Option<Ghost> {
+        let sessions = self.sessions();
+        let mode = self.settings.game_mode;
+        if mode.is_text_based() {
+            // the same quote length, language, text or book before the units are compared
+            let character_sets = self.character_sets();
+            let units: Vec<String> = std::iter::once(&self.target)
+                .chain(&self.char_future)
+                .chain(&self.passage)
+                .cloned()
+                .collect();
+            Ghost::best_run(sessions, |s| {
+                s.mode == mode
+                    && s.character_sets == character_sets
+                    && metrics::typed_units(&s.keystrokes) == units
+            })
+        } else if self.is_timed() {
+            let character_sets = self.character_sets();
+            let duration_ms = i64::from(self.settings.total_time_sec) * 1000;
+            Ghost::best_run(sessions, |s| {
+                s.mode == mode
+                    && !s.hardcore
+                    && s.duration_ms == duration_ms
+                    && s.character_sets == character_sets
+            })
+        } else {
+            None
+        }
+    }
+
+    /// the session history, empty when the game doesn't persist
+    fn sessions(&self) -> &[SessionRecord] {
+        self.history.as_deref().unwrap_or_default()
+    }
+
+    /// recomputes the adaptive weights from the session history,
+    /// falls back to uniform picking when there is no history yet
+    pub fn update_char_weights(&mut self) {
+        self.char_weights = if self.settings.adaptive {
+            adaptive::char_weights(
+                &self.char_vec,
+                self.sessions(),
+                Local::now().timestamp(),
+            )
+        } else {
+            None
+        };
+        if self.base_weights.iter().any(|w| *w != 1.0) {
+            let mut weights = self
+                .char_weights
+                .take()
+                .unwrap_or_else(|| vec![1.0; self.char_vec.len()]);
+            for (weight, base) in weights.iter_mut().zip(&self.base_weights) {
+                *weight *= base;
+            }
+            self.char_weights = Some(weights);
+        }
+        if let Some(focus) = self.lesson.as_ref().and_then(|p| p.focus_key(&self.layout)) {
+            let mut weights = self
+                .char_weights
+                .take()
+                .unwrap_or_else(|| vec![1.0; self.char_vec.len()]);
+            for (weight, c) in weights.iter_mut().zip(&self.char_vec) {
+                if *c == focus {
+                    *weight *= LESSON_FOCUS_WEIGHT;
+                }
+            }
+            self.char_weights = Some(weights);
+        }
+    }
+
+    fn push_hist(&mut self, entry: String, correct: bool) {
+        self.char_hist.remove(0);
+        self.char_hist.push(entry);
+        self.correct_hist.remove(0);
+        self.correct_hist.push(correct);
+    }
+
+    /// whether the game ends after `total_time_sec`, a replay ends with its recording
+    pub fn is_timed(&self) -> bool {
+        !self.settings.game_mode.is_text_based()
+            && !self.settings.hardcore
+            && self.replay.is_none()
+    }
+
+    /// correct characters per minute over the last few seconds
+    pub fn rolling_cpm(&self) -> f64 {
+        let window = Duration::seconds(HARDCORE_WINDOW_SEC).min(self.time);
+        if window <= Duration::zero() {
+            return 0.0;
+        }
+        let since = self.time - window;
+        let correct = self
+            .keystrokes
+            .iter()
+            .filter(|k| k.correct && k.time > since)
+            .count() as f64;
+        correct * 60_000.0 / window.num_milliseconds() as f64
+    }
+
+    /// the speed needed to stay alive in hardcore mode, rising every minute
+    pub fn required_cpm(&self) -> f64 {
+        let minutes = self.time.num_milliseconds() as f64 / 60_000.0;
+        f64::from(self.settings.hardcore_start_cpm)
+            + f64::from(self.settings.hardcore_ramp_cpm) * minutes
+    }
+
+    /// ends timed games once the configured time has passed, and
+    /// hardcore games once the player is too slow
+    pub fn check_game_over(&mut self) {
+        if !self.play {
+            return;
+        }
+        if self.is_timed() && self.time >= Duration::seconds(self.settings.total_time_sec.into()) {
+            self.finish(GameEnd::TimeUp);
+        } else if self.settings.hardcore
+            && self.time >= Duration::seconds(HARDCORE_WINDOW_SEC)
+            && self.rolling_cpm() < self.required_cpm()
+        {
+            self.finish(GameEnd::TooSlow);
+        }
+    }
+
+    /// ends the game and stores it in the session history
+    pub fn finish(&mut self, end: GameEnd) {
+        self.play = false;
+        self.end = Some(end);
+        if self.keystrokes.is_empty() || !self.persist {
+            return;
+        }
+        let record = self.session_record();
+        if let Err(error) = session_history::append_session(&record) {
+            self.errors.push(AppError::SaveFailed("session history", error));
+        }
+        // the next restart races against this game too
+        if let Some(history) = &mut self.history {
+            history.push(record);
+        }
+        if let Some(progress) = &mut self.lesson {
+            self.unlocked_key = progress.update(
+                &self.keystrokes,
+                &self.layout,
+                self.settings.lesson_target_cpm,
+                self.settings.lesson_target_accuracy,
+            );
+            if let Err(error) = progress.write() {
+                self.errors
+                    .push(AppError::SaveFailed("lesson progress", io::Error::other(error)));
+            }
+        }
+        if let (Some(book), GameEnd::TextFinished) = (&mut self.book, end) {
+            let correct = self.keystrokes.iter().filter(|k| k.correct).count() as u32;
+            book.finish_passage(
+                self.book_passage.chars().count(),
+                self.keystrokes.len() as u32,
+                correct,
+                self.time.num_milliseconds(),
+            );
+            // a bookshelf that can't be read is not written over
+            let saved = match Bookshelf::read() {
+                Ok(mut shelf) => {
+                    shelf.update(book);
+                    shelf
+                        .write()
+                        .map_err(|e| AppError::SaveFailed("bookshelf", io::Error::other(e)))
+                }
+                Err(error) => Err(AppError::BadSaveFile("bookshelf", error)),
+            };
+            if let Err(error) = saved {
+                self.errors.push(error);
+            }
+        }
+    }
+
+    /// the character sets or text source the session was played with
+    pub fn character_sets(&self) -> Vec<String> {
+        match self.settings.game_mode {
+            GameMode::Characters => get_dict(&self.settings)
+                .into_iter()
+                .filter(|entry| entry.val)
+                .map(|entry| entry.key)
+                .chain(self.custom_sets.iter().map(|set| set.id.clone()))
+                .collect(),
+            GameMode::Words => vec![self.settings.word_list.file_name().to_string()],
+            GameMode::Quote => vec![self.settings.quote_length.name().to_lowercase()],
+            GameMode::Lesson => vec!["lesson".to_string()],
+            GameMode::Ngrams => vec![self.settings.ngram_list.file_name().to_string()],
+            GameMode::Code => vec![self.settings.code_language.file_name().to_string()],
+            GameMode::Text => self.text.iter().map(|text| text.name.clone()).collect(),
+            GameMode::Book => self.book.iter().map(|book| book.name.clone()).collect(),
+        }
+    }
+
+    pub fn session_record(&self) -> SessionRecord {
+        SessionRecord {
+            version: HISTORY_VERSION,
+            timestamp: Local::now().timestamp(),
+            mode: self.settings.game_mode,
+            hardcore: self.settings.hardcore,
+            character_sets: self.character_sets(),
+            duration_ms: self.session_duration().num_milliseconds(),
+            metrics: self.metrics(),
+            key_results: session_history::key_results(&self.keystrokes),
+            keystrokes: self.keystrokes.clone(),
+        }
     }
 
     pub fn compare_pressed_char(&mut self, character: char) {
+        self.check_game_over();
         if !self.play {
             return;
         }
-        self.char_hist.remove(0);
-        self.char_hist.push(character);
 
-        if self.random_char == character {
-            self.random_char = self.char_future[0];
-            self.char_future.remove(0);
-            self.char_future
-                .push(load_chars::chose_random(self.char_vec.to_owned()));
+        let expected = self.expected_char();
+        let correct = expected == character;
+        // a wrong key does not move on, so the previous wrong key was at the same position
+        let correction = correct
+            && self
+                .keystrokes
+                .last()
+                .is_some_and(|k| !k.correct && k.unit == self.units_done);
+        self.keystrokes.push(Keystroke {
+            time: self.time,
+            expected,
+            typed: character,
+            correct,
+            correction,
+            unit: self.units_done,
+        });
+        if self.settings.hardcore {
+            self.peak_cpm = self.peak_cpm.max(self.rolling_cpm());
+        }
+
+        if correct {
+            self.target_pos += 1;
+            if self.target_pos >= self.target.chars().count() {
+                let finished = std::mem::take(&mut self.target);
+                let correct = !self.target_error;
+                self.push_hist(finished, correct);
+                self.units_done += 1;
+                self.target_pos = 0;
+                self.target_error = false;
 
-            self.correct_hist.remove(0);
-            self.correct_hist.push(true);
-            self.score += 1;
+                if self.char_future.is_empty() {
+                    // the whole text has been typed
+                    self.finish(GameEnd::TextFinished);
+                    return;
+                }
+                self.target = self.char_future.remove(0);
+                if let Some(unit) = self.next_unit() {
+                    self.char_future.push(unit);
+                }
+            }
         } else {
-            self.correct_hist.remove(0);
-            self.correct_hist.push(false);
+            self.target_error = true;
+            if self.settings.hardcore
+                && self.mistakes() >= u32::from(self.settings.hardcore_mistakes)
+            {
+                self.finish(GameEnd::TooManyMistakes);
+            }
+            // in character mode every wrong keypress is shown in the history,
+            // in word mode the word is marked as wrong once it is finished
+            if self.settings.game_mode.is_character_based() {
+                self.push_hist(character.to_string(), false);
+            }
         }
     }
 
+    /// whether space is part of the text instead of restarting the game
+    pub fn types_spaces(&self) -> bool {
+        self.play && !self.settings.game_mode.is_character_based()
+    }
+
+    /// length of the session: the configured time for timed modes,
+    /// the time it took to finish the text for text based modes
+    pub fn session_duration(&self) -> Duration {
+        let total = Duration::seconds(self.settings.total_time_sec.into());
+        if !self.is_timed() || self.time < total {
+            self.time
+        } else {
+            total
+        }
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        Metrics::from_keystrokes(&self.keystrokes, self.session_duration())
+    }
+
+    /// correctly typed characters
+    pub fn score(&self) -> u32 {
+        self.keystrokes.iter().filter(|k| k.correct).count() as u32
+    }
+
+    pub fn mistakes(&self) -> u32 {
+        self.keystrokes.iter().filter(|k| !k.correct).count() as u32
+    }
+
     pub fn color_returner(&self, boolean: bool) -> Color {
         if boolean {
             Color::Rgb(66, 190, 66)
@@ -144,10 +692,47 @@ impl GameLogic {
             Color::Rgb(190, 66, 66)
         }
     }
+}
+
+impl Page for GameLogic {
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Action {
+        self.get_time();
+        match key_event.code {
+            KeyCode::Esc => return Action::Pop,
+            KeyCode::Char(' ') if !self.types_spaces() => return self.restart(),
+            KeyCode::Tab => return self.restart(),
+            KeyCode::Enter if self.types_spaces() => self.compare_pressed_char('\n'),
+            KeyCode::Char(code) => {
+                // when emulating a layout, the key pressed on the OS layout
+                // types the character on the same key of the practiced layout
+                let code = match &self.os_layout {
+                    Some(os_layout) => self.layout.translate(os_layout, code),
+                    None => code,
+                };
+                self.compare_pressed_char(code)
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn tick(&mut self) {
+        self.get_time();
+    }
+
+    fn take_errors(&mut self) -> Vec<AppError> {
+        std::mem::take(&mut self.errors)
+    }
 
-    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
         let title = Title::from(" CrabType ".bold());
-        let instructions = Title::from(Line::from(vec![" quit: <esc> | restart: <space> ".into()]));
+        let restart_key = if self.types_spaces() { "<tab>" } else { "<space>" };
+        // there is nothing left to restart once a book is finished
+        let restart = if self.book_finished() { "menu" } else { "restart" };
+        let instructions = Title::from(Line::from(vec![format!(
+            " quit: <esc> | {restart}: {restart_key} "
+        )
+        .into()]));
         let block = Block::default()
             .title(title.alignment(Alignment::Center))
             .title(
@@ -159,9 +744,7 @@ impl GameLogic {
             .style(Style::default().fg(Color::Yellow).bg(Color::Black))
             .border_set(border::THICK);
 
-        if self.time >= Duration::seconds(self.settings.total_time_sec.into()) {
-            self.play = false
-        }
+        self.check_game_over();
 
         if self.play {
             self.render_game(area, buf, block);
@@ -169,21 +752,150 @@ impl GameLogic {
             self.render_result(area, buf, block);
         }
     }
+}
 
+impl GameLogic {
     pub fn render_game(&self, area: Rect, buf: &mut Buffer, block: Block) {
-        let (timer_layout, letter_line_layout) = self.split_game_layout(area);
+        let (timer_layout, lesson_layout, ghost_layout, letter_line_layout, hint_layout) =
+            self.split_game_layout(area);
         Paragraph::new("").block(block).render(area, buf);
 
+        // Lesson progress
+        if let Some(progress) = &self.lesson {
+            let mut keys = vec![];
+            let order = lesson_progress::lesson_order(&self.layout);
+            for (i, key) in order.iter().copied().enumerate() {
+                if i < progress.unlocked {
+                    let done = progress.key_progress(
+                        key,
+                        self.settings.lesson_target_cpm,
+                        self.settings.lesson_target_accuracy,
+                    );
+                    let color = if done >= 1.0 {
+                        self.color_returner(true)
+                    } else {
+                        Color::White
+                    };
+                    keys.push(Span::styled(
+                        format!("{key} {:.0}%", 100.0 * done),
+                        Style::new().fg(color).add_modifier(Modifier::BOLD),
+                    ));
+                } else {
+                    keys.push(Span::styled(
+                        key.to_string(),
+                        Style::new().fg(Color::Rgb(128, 128, 128)),
+                    ));
+                }
+                keys.push(Span::from("  "));
+            }
+            Paragraph::new(vec![
+                text::Line::from(keys),
+                text::Line::from(format!(
+                    "unlocked {}/{} keys | target: {} char/min at {}% accuracy",
+                    progress.unlocked,
+                    order.len(),
+                    self.settings.lesson_target_cpm,
+                    self.settings.lesson_target_accuracy
+                )),
+            ])
+            .wrap(Wrap { trim: true })
+            .centered()
+            .render(lesson_layout, buf);
+        }
+
+        // Finger hint
+        if self.settings.ten_finger_typing {
+            let target = self.expected_char();
+            let hint = match fingers::finger_for(&self.layout, target) {
+                Some(finger) => Span::styled(
+                    format!(
+                        "{}: {}",
+                        target,
+                        fingers::hint(&self.layout, target).unwrap_or_default()
+                    ),
+                    Style::new().fg(finger.color()).add_modifier(Modifier::BOLD),
+                ),
+                None => Span::from(format!("{target}: no finger hint")),
+            };
+            Paragraph::new(hint)
+                .centered()
+                .render(Rect { height: 1, ..hint_layout }, buf);
+
+            let (width, height) = KeyboardHeatmap::size();
+            let keyboard_area = Rect::new(
+                hint_layout.x + hint_layout.width.saturating_sub(width) / 2,
+                hint_layout.y + 2,
+                width.min(hint_layout.width),
+                height.min(hint_layout.height.saturating_sub(2)),
+            );
+            FingerKeyboard {
+                target,
+                layout: &self.layout,
+            }
+            .render(keyboard_area, buf);
+        }
+
+        // Ghost race
+        if let Some(ghost) = &self.ghost {
+            let score = self.score() as usize;
+            let ghost_score = ghost.score_at(self.time);
+            let goal = ghost.goal.max(score).max(1);
+            let width = usize::from(ghost_layout.width.saturating_sub(24));
+            let bar = |done: usize| {
+                let filled = (width * done.min(goal) / goal).min(width);
+                format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+            };
+            let lead = score as i64 - ghost_score as i64;
+            let race = match lead.cmp(&0) {
+                std::cmp::Ordering::Greater => Span::styled(
+                    format!("{lead} ahead"),
+                    Style::new().fg(self.color_returner(true)),
+                ),
+                std::cmp::Ordering::Less => Span::styled(
+                    format!("{} behind", -lead),
+                    Style::new().fg(self.color_returner(false)),
+                ),
+                std::cmp::Ordering::Equal => Span::from("even"),
+            };
+            Paragraph::new(vec![
+                text::Line::from(format!("you  {} {score:>5}", bar(score))),
+                text::Line::from(format!("best {} {ghost_score:>5}", bar(ghost_score))),
+                text::Line::from(vec![
+                    Span::from(format!("best run {:.1} net WPM | ", ghost.net_wpm)),
+                    race,
+                ]),
+            ])
+            .centered()
+            .render(ghost_layout, buf);
+        }
+
         // Timer paragraph
-        let timer = (i64::from(self.settings.total_time_sec) - self.time.num_seconds()).to_string();
+        // text based and hardcore modes count up, timed modes count down
+        let timer = if self.settings.hardcore {
+            format!(
+                "{} | {:.0} / {:.0} char/min | lives: {}",
+                self.time.num_seconds(),
+                self.rolling_cpm(),
+                self.required_cpm(),
+                u32::from(self.settings.hardcore_mistakes).saturating_sub(self.mistakes())
+            )
+        } else if !self.is_timed() {
+            self.time.num_seconds().to_string()
+        } else {
+            (i64::from(self.settings.total_time_sec) - self.time.num_seconds()).to_string()
+        };
         let mut _ascii_time = "".to_string();
         for _char in timer.chars() {
             _ascii_time += translator::translator(&_char.to_string());
         }
 
+        let emulation = match &self.os_layout {
+            Some(os) => format!("{} emulated on {}", self.layout.name, os.name),
+            None => " ".to_string(),
+        };
         Paragraph::new(vec![
             text::Line::from(" CrabType ".bold()),
-            text::Line::from(" "),
+            text::Line::from(emulation),
             text::Line::from(" "),
             text::Line::from(timer),
         ])
@@ -191,15 +903,21 @@ impl GameLogic {
         .block(Block::new())
         .render(timer_layout, buf);
 
+        if self.settings.game_mode == GameMode::Code {
+            self.render_code(letter_line_layout[0].union(letter_line_layout[2]), buf);
+            return;
+        }
+
         // History paragraph
         let mut hist_line = vec![];
         if self.hist_amount != 0 {
             for (i, _char) in self.char_hist.clone().iter().enumerate() {
-                if _char.eq(&' ') {
+                if _char == " " {
                     continue;
                 }
+                // the space typed after a word is shown as the gap between words
                 hist_line.push(Span::styled(
-                    _char.to_string(),
+                    _char.trim_end().to_owned(),
                     Style::new().fg(self.color_returner(self.correct_hist[i])),
                 ));
                 hist_line.push(Span::from(" "));
@@ -222,18 +940,43 @@ impl GameLogic {
 
 
         // Word to guess paragraph
-        let word_to_type = self.random_char.to_string();
-        let mut _ascii_word: String = "".to_string();
-        if self.settings.large_char{
-            _ascii_word = translator::translator(&word_to_type).to_string();
-        }
-        else{
-            _ascii_word = word_to_type;
+        if self.settings.game_mode.is_character_based() {
+            let word_to_type = self.target.to_string();
+            let mut _ascii_word: String = "".to_string();
+            if self.settings.large_char{
+                _ascii_word = translator::translator(&word_to_type).to_string();
+            }
+            else{
+                _ascii_word = word_to_type;
+            }
+            Paragraph::new(_ascii_word)
+                .centered()
+                .block(Block::new())
+                .render(letter_line_layout[1], buf);
+        } else {
+            let split = self
+                .target
+                .char_indices()
+                .nth(self.target_pos)
+                .map_or(self.target.len(), |(i, _)| i);
+            let (typed, remaining) = self.target.split_at(split);
+            // space is expected
+            let remaining = if remaining == " " { "␣" } else { remaining };
+            let mut v_word = vec![];
+            if self.settings.large_char{
+                for _ in 0..3{
+                    v_word.push(text::Line::from(" "))
+                }
+            }
+            v_word.push(text::Line::from(vec![
+                Span::styled(typed.to_string(), Style::new().fg(self.color_returner(!self.target_error))),
+                Span::styled(remaining.to_string(), Style::new().add_modifier(Modifier::BOLD)),
+            ]));
+            Paragraph::new(v_word)
+                .centered()
+                .block(Block::new())
+                .render(letter_line_layout[1], buf);
         }
-        Paragraph::new(_ascii_word)
-            .centered()
-            .block(Block::new())
-            .render(letter_line_layout[1], buf);
 
         // Future Paragraph
         let mut future_line = vec![];
@@ -241,7 +984,7 @@ impl GameLogic {
             for u in self.char_future.clone() {
                 future_line.push(Span::from(" "));
                 future_line.push(Span::styled(
-                    u.to_string(),
+                    u.trim_end().to_owned(),
                     Style::new().fg(Color::Rgb(128, 128, 128)),
                 ));
             }
@@ -262,29 +1005,263 @@ impl GameLogic {
         .render(letter_line_layout[2], buf);
     }
 
+    /// the snippet with syntax highlighting, the typed part of the current line
+    /// colored by correctness and the lines already typed dimmed
+    fn render_code(&self, area: Rect, buf: &mut Buffer) {
+        let Some(snippet) = &self.snippet else {
+            return;
+        };
+        let cursor = Style::new().add_modifier(Modifier::REVERSED);
+        let mut lines = vec![];
+        for (i, line) in snippet.lines().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            let styles = highlight::highlight(line, self.settings.code_language);
+            let indent = chars.len() - line.trim_start().chars().count();
+            let typed = match i.cmp(&self.units_done) {
+                std::cmp::Ordering::Less => chars.len(),
+                std::cmp::Ordering::Equal => indent + self.target_pos,
+                std::cmp::Ordering::Greater => 0,
+            };
+
+            let mut spans = vec![];
+            for (j, c) in chars.iter().enumerate() {
+                let style = if i < self.units_done {
+                    styles[j].add_modifier(Modifier::DIM)
+                } else if j < typed {
+                    Style::new().fg(self.color_returner(!self.target_error))
+                } else if i == self.units_done && j == typed {
+                    styles[j].patch(cursor)
+                } else {
+                    styles[j]
+                };
+                spans.push(Span::styled(c.to_string(), style));
+            }
+            if i == self.units_done && typed >= chars.len() && self.expected_char() == '\n' {
+                // enter is expected
+                spans.push(Span::styled("⏎", cursor));
+            }
+            lines.push(text::Line::from(spans));
+        }
+
+        // keep the current line in view
+        let scroll = self.units_done.saturating_sub(usize::from(area.height / 2));
+        let width = snippet.lines().map(|l| l.chars().count() + 1).max().unwrap_or(0) as u16;
+        let code_area = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            width: width.min(area.width),
+            ..area
+        };
+        Paragraph::new(lines)
+            .scroll((scroll as u16, 0))
+            .render(code_area, buf);
+    }
+
     pub fn render_result(&self, area: Rect, buf: &mut Buffer, block: Block) {
-        let text2 = vec![
+        let metrics = self.metrics();
+        let mut text2 = vec![
             text::Line::from(vec![Span::from("You score is: ")]),
             text::Line::from(" "),
-            text::Line::from(vec![Span::from(self.score.to_string())]),
+            text::Line::from(vec![Span::from(self.score().to_string())]),
+            text::Line::from(" "),
+            text::Line::from(format!(
+                "WPM: {:.1} | net WPM: {:.1} | CPM: {:.1}",
+                metrics.gross_wpm, metrics.net_wpm, metrics.cpm
+            )),
+            text::Line::from(format!(
+                "Accuracy: {:.1}% | corrected accuracy: {:.1}% | consistency: {:.1}%",
+                metrics.raw_accuracy, metrics.corrected_accuracy, metrics.consistency
+            )),
         ];
+        if !self.settings.game_mode.is_character_based() {
+            let unit = match self.settings.game_mode {
+                GameMode::Ngrams => "n-grams",
+                GameMode::Code => "lines",
+                _ => "words",
+            };
+            text2.push(text::Line::from(" "));
+            text2.push(text::Line::from(vec![Span::from(format!(
+                "Correct {unit}: {}",
+                metrics::units_correct(&self.keystrokes, self.units_done)
+            ))]));
+        }
+        if self.settings.game_mode == GameMode::Ngrams {
+            let mut ngram_results: Vec<NgramResult> =
+                metrics::ngram_results(&self.keystrokes, self.units_done)
+                    .into_iter()
+                    .filter(|r| r.ngram.chars().count() > 1)
+                    .collect();
+            // slowest n-gram first
+            ngram_results
+                .sort_by(|a, b| b.mean_transition_ms().total_cmp(&a.mean_transition_ms()));
+            let slowest: Vec<String> = ngram_results
+                .iter()
+                .take(5)
+                .map(|r| format!("{} {:.0}ms", r.ngram, r.mean_transition_ms()))
+                .collect();
+            if !slowest.is_empty() {
+                text2.push(text::Line::from(format!(
+                    "Slowest n-grams: {}",
+                    slowest.join(" | ")
+                )));
+            }
+        }
+        if self.settings.hardcore {
+            let reason = match self.end {
+                Some(GameEnd::TooSlow) => "too slow",
+                Some(GameEnd::TooManyMistakes) => "too many wrong keys",
+                _ => "finished",
+            };
+            text2.push(text::Line::from(" "));
+            text2.push(text::Line::from(format!(
+                "Survived {:.1} seconds ({reason}) | peak speed: {:.0} char/min",
+                self.time.num_milliseconds() as f64 / 1000.0,
+                self.peak_cpm
+            )));
+        }
+        if self.settings.ten_finger_typing {
+            let mut finger_results = fingers::finger_results(&self.layout, &self.keystrokes);
+            // weakest finger first
+            finger_results.sort_by(|a, b| a.accuracy().total_cmp(&b.accuracy()));
+            let weakest: Vec<String> = finger_results
+                .iter()
+                .take(3)
+                .map(|r| format!("{} {:.0}%", r.finger.name(), r.accuracy()))
+                .collect();
+            text2.push(text::Line::from(" "));
+            text2.push(text::Line::from(format!(
+                "Weakest fingers: {}",
+                weakest.join(" | ")
+            )));
+        }
+        if let Some(progress) = &self.lesson {
+            text2.push(text::Line::from(" "));
+            match self.unlocked_key {
+                Some(key) => text2.push(text::Line::from(format!("New key unlocked: {key}"))),
+                None => {
+                    let missing: Vec<String> = progress
+                        .unlocked_keys(&self.layout)
+                        .into_iter()
+                        .filter(|k| {
+                            progress.key_progress(
+                                *k,
+                                self.settings.lesson_target_cpm,
+                                self.settings.lesson_target_accuracy,
+                            ) < 1.0
+                        })
+                        .map(|k| k.to_string())
+                        .collect();
+                    if !missing.is_empty() {
+                        text2.push(text::Line::from(format!(
+                            "Keys below target: {}",
+                            missing.join(" ")
+                        )));
+                    }
+                }
+            }
+        }
+        if self.settings.game_mode.is_text_based() {
+            text2.push(text::Line::from(" "));
+            text2.push(text::Line::from(vec![Span::from(format!(
+                "Finished in {} seconds",
+                self.time.num_seconds()
+            ))]));
+        }
+        if let Some(text) = &self.text {
+            text2.push(text::Line::from(format!(
+                "{}: part {} of {}",
+                text.name,
+                text.session + 1,
+                text.sessions.len()
+            )));
+            if !text.unsupported.is_empty() {
+                let removed: String = text.unsupported.iter().collect();
+                text2.push(text::Line::from(format!(
+                    "Removed characters that cannot be typed: {removed}"
+                )));
+            }
+        }
+        if let Some(book) = &self.book {
+            if book.is_finished() {
+                text2.push(text::Line::from(" "));
+                text2.push(text::Line::from(format!("You have typed {} to the end!", book.name)));
+            }
+            text2.push(text::Line::from(format!(
+                "{}: {:.1}% typed | {} sessions, {:.0} char/min, {:.1}% accuracy",
+                book.name,
+                book.progress(),
+                book.sessions,
+                book.cpm(),
+                book.accuracy()
+            )));
+        }
+        if let Some(quote) = &self.quote {
+            text2.push(text::Line::from(" "));
+            text2.push(text::Line::from(vec![Span::from(format!(
+                "\"{}\"",
+                quote.text
+            ))]));
+            text2.push(text::Line::from(vec![Span::styled(
+                format!("- {}, {}", quote.author, quote.source),
+                Style::new().add_modifier(Modifier::ITALIC),
+            )]));
+        }
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let mut text_area = inner;
+
+        if self.settings.result_heatmap {
+            let (width, height) = KeyboardHeatmap::size();
+            let heatmap_area = Rect::new(
+                inner.x + inner.width.saturating_sub(width) / 2,
+                inner.bottom().saturating_sub(height),
+                width.min(inner.width),
+                height.min(inner.height),
+            );
+            text_area.height = inner.height.saturating_sub(height);
+            KeyboardHeatmap::new(
+                &session_history::key_results(&self.keystrokes),
+                HeatmapMetric::ErrorRate,
+                &self.layout,
+            )
+            .render(heatmap_area, buf);
+        }
+
         Paragraph::new(text2)
+            .wrap(Wrap { trim: true })
             .centered()
-            .block(block)
-            .render(area, buf);
+            .render(text_area, buf);
     }
 
-    fn split_game_layout(&self, area: Rect) -> (Rect, Rc<[Rect]>) {
+    fn split_game_layout(&self, area: Rect) -> (Rect, Rect, Rect, Rc<[Rect]>, Rect) {
         // split game in:
         // +---------------------------------+
         // |              Timer              |
+        // +---------------------------------+
+        // |     Lesson keys (lesson mode)   |
+        // +---------------------------------+
+        // |   Race against the best run     |
         // +---------+--------------+--------+
         // | History | Word to type | Future |
+        // +---------+--------------+--------+
+        // |  Finger hint (10 finger typing) |
         // +---------------------------------+
 
+        let hint_height = if self.settings.ten_finger_typing {
+            KeyboardHeatmap::size().1 + 1
+        } else {
+            0
+        };
+        let lesson_height = if self.lesson.is_some() { 3 } else { 0 };
+        let ghost_height = if self.ghost.is_some() { 3 } else { 0 };
         let outer_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Percentage(10), Constraint::Percentage(90)])
+            .constraints(vec![
+                Constraint::Percentage(10),
+                Constraint::Length(lesson_height),
+                Constraint::Length(ghost_height),
+                Constraint::Min(0),
+                Constraint::Length(hint_height),
+            ])
             .split(area);
 
         let inner_layout = Layout::default()
@@ -294,8 +1271,202 @@ impl GameLogic {
                 Constraint::Percentage(20),
                 Constraint::Percentage(40),
             ])
-            .split(outer_layout[1]);
+            .split(outer_layout[3]);
+
+        (
+            outer_layout[0],
+            outer_layout[1],
+            outer_layout[2],
+            inner_layout,
+            outer_layout[4],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::FakeClock;
+
+    /// a game that reads and saves nothing, timed by the returned clock
+    fn new_game(settings: Settings) -> (GameLogic, FakeClock) {
+        let clock = FakeClock::default();
+        let mut game = GameLogic::new(settings, Box::new(clock.clone())).unwrap();
+        game.persist = false;
+        game.reset().unwrap();
+        (game, clock)
+    }
+
+    /// presses the expected key, or a key that is never expected, `after` the last one
+    fn press(game: &mut GameLogic, clock: &FakeClock, after: Duration, correct: bool) {
+        clock.advance(after);
+        game.get_time();
+        let key = if correct {
+            game.expected_char()
+        } else {
+            char::REPLACEMENT_CHARACTER
+        };
+        game.compare_pressed_char(key);
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn timed_game_ends_at_total_time() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Characters,
+            total_time_sec: 5,
+            ..Settings::default()
+        });
+        press(&mut game, &clock, Duration::milliseconds(4999), true);
+        game.check_game_over();
+        assert!(game.play);
+
+        clock.advance(Duration::milliseconds(1));
+        game.get_time();
+        game.check_game_over();
+        assert_eq!(game.end, Some(GameEnd::TimeUp));
+
+        // keys after the end are not logged and the clock stays stopped
+        press(&mut game, &clock, Duration::seconds(1), true);
+        assert_eq!(game.keystrokes.len(), 1);
+        assert_eq!(game.time, Duration::seconds(5));
+        assert_eq!(game.session_duration(), Duration::seconds(5));
+    }
+
+    #[test]
+    fn replay_has_no_time_limit() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Characters,
+            total_time_sec: 5,
+            ..Settings::default()
+        });
+        game.use_replay(vec!["a".to_string(), "…".to_string()]);
+        game.reset().unwrap();
+        clock.advance(Duration::seconds(300));
+        game.get_time();
+        game.compare_pressed_char('a');
+        assert!(game.play);
+        assert_eq!(game.session_duration(), Duration::seconds(300));
+    }
+
+    #[test]
+    fn hardcore_waits_for_the_first_window() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Characters,
+            hardcore: true,
+            ..Settings::default()
+        });
+        clock.advance(Duration::milliseconds(HARDCORE_WINDOW_SEC * 1000 - 1));
+        game.get_time();
+        game.check_game_over();
+        assert!(game.play);
+
+        clock.advance(Duration::milliseconds(1));
+        game.get_time();
+        game.check_game_over();
+        assert_eq!(game.end, Some(GameEnd::TooSlow));
+    }
+
+    #[test]
+    fn hardcore_speed_limit_ramps_up() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Characters,
+            hardcore: true,
+            hardcore_start_cpm: 60,
+            hardcore_ramp_cpm: 60,
+            ..Settings::default()
+        });
+        // a steady 120 keys per minute, the limit passes it after a minute
+        while game.play && game.time < Duration::minutes(2) {
+            press(&mut game, &clock, Duration::milliseconds(500), true);
+        }
+        assert_eq!(game.end, Some(GameEnd::TooSlow));
+        // the key at the end is not counted yet: 19 keys in the last 10 seconds
+        // are 114 char/min, the limit is 60 + 1 char/min per second
+        assert_eq!(game.time, Duration::milliseconds(54_500));
+        // sampled on every key from the first one on
+        assert_close(game.peak_cpm, 120.0);
+    }
+
+    #[test]
+    fn hardcore_ends_on_too_many_mistakes() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Characters,
+            hardcore: true,
+            hardcore_mistakes: 2,
+            ..Settings::default()
+        });
+        press(&mut game, &clock, Duration::milliseconds(200), false);
+        assert!(game.play);
+        press(&mut game, &clock, Duration::milliseconds(200), false);
+        assert_eq!(game.end, Some(GameEnd::TooManyMistakes));
+    }
+
+    #[test]
+    fn quote_is_typed_with_the_spaces_between_words() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Quote,
+            ..Settings::default()
+        });
+        let text = game.quote.clone().unwrap().text;
+        assert!(game.types_spaces());
+        while game.play {
+            press(&mut game, &clock, Duration::milliseconds(100), true);
+        }
+        assert_eq!(game.end, Some(GameEnd::TextFinished));
+        let typed: String = game.keystrokes.iter().map(|k| k.typed).collect();
+        assert_eq!(typed, text.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+
+    #[test]
+    fn ngrams_are_typed_with_a_space_after_them() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Ngrams,
+            ..Settings::default()
+        });
+        assert!(game.types_spaces());
+        let units: Vec<String> = std::iter::once(&game.target)
+            .chain(&game.char_future)
+            .take(3)
+            .cloned()
+            .collect();
+        while game.units_done < 3 {
+            press(&mut game, &clock, Duration::milliseconds(100), true);
+        }
+        assert_eq!(metrics::typed_units(&game.keystrokes), units);
+        assert!(units.iter().all(|unit| unit.ends_with(' ')));
+        for result in metrics::ngram_results(&game.keystrokes, game.units_done) {
+            assert!(units.contains(&format!("{} ", result.ngram)));
+        }
+    }
+
+    #[test]
+    fn metrics_are_computed_from_the_keystrokes() {
+        let (mut game, clock) = new_game(Settings {
+            game_mode: GameMode::Characters,
+            total_time_sec: 10,
+            ..Settings::default()
+        });
+        for correct in [true, true, false, true, true, true, true, true, true, true] {
+            press(&mut game, &clock, Duration::milliseconds(600), correct);
+        }
+        clock.set(Duration::seconds(10));
+        game.get_time();
+        game.check_game_over();
+        assert_eq!(game.end, Some(GameEnd::TimeUp));
+        assert_eq!(game.score(), 9);
+        assert_eq!(game.mistakes(), 1);
 
-        (outer_layout[0], inner_layout)
+        // 10 keys in a sixth of a minute, one of them wrong
+        let metrics = game.metrics();
+        assert_close(metrics.gross_wpm, 12.0);
+        assert_close(metrics.net_wpm, 6.0);
+        assert_close(metrics.cpm, 54.0);
+        assert_close(metrics.raw_accuracy, 90.0);
+        assert_close(metrics.corrected_accuracy, 800.0 / 9.0);
+        assert_close(metrics.consistency, 100.0);
     }
 }
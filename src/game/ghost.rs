@@ -0,0 +1,41 @@
+use chrono::Duration;
+
+use crate::game::metrics::Keystroke;
+use crate::history::session_history::SessionRecord;
+
+/// the best earlier run of the same mode and text, raced against during a game
+#[derive(Debug, Clone)]
+pub struct Ghost {
+    pub keystrokes: Vec<Keystroke>,
+    pub net_wpm: f64,
+    // correct keystrokes of the whole run: the length of the text in text
+    // based modes, the final score in timed modes
+    pub goal: usize,
+}
+
+impl Ghost {
+    /// the run with the highest net wpm among the `sessions` that match
+    pub fn best_run<F>(sessions: &[SessionRecord], matches: F) -> Option<Ghost>
+    where
+        F: Fn(&SessionRecord) -> bool,
+    {
+        let best = sessions
+            .iter()
+            .filter(|s| !s.keystrokes.is_empty() && matches(s))
+            .max_by(|a, b| a.metrics.net_wpm.total_cmp(&b.metrics.net_wpm))?;
+        Some(Ghost {
+            keystrokes: best.keystrokes.clone(),
+            net_wpm: best.metrics.net_wpm,
+            goal: best.keystrokes.iter().filter(|k| k.correct).count(),
+        })
+    }
+
+    /// correct keys the ghost had typed `time` into its run
+    pub fn score_at(&self, time: Duration) -> usize {
+        self.keystrokes
+            .iter()
+            .take_while(|k| k.time <= time)
+            .filter(|k| k.correct)
+            .count()
+    }
+}
@@ -0,0 +1,98 @@
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::settings::settings_struct::CodeLanguage;
+
+static RUST_KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "dyn", "else", "enum", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "use", "where",
+    "while",
+];
+static PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "class", "def", "elif", "else", "except", "False", "finally", "for", "from",
+    "if", "import", "in", "is", "lambda", "None", "not", "or", "pass", "raise", "return",
+    "True", "try", "while", "with", "yield",
+];
+static C_KEYWORDS: &[&str] = &[
+    "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+    "float", "for", "if", "int", "long", "NULL", "return", "size_t", "sizeof", "static",
+    "struct", "switch", "typedef", "unsigned", "void", "while", "#include", "#define",
+];
+static SHELL_KEYWORDS: &[&str] = &[
+    "case", "do", "done", "echo", "elif", "else", "esac", "exit", "export", "fi", "for",
+    "function", "if", "in", "local", "read", "then", "while",
+];
+
+fn keywords(language: CodeLanguage) -> &'static [&'static str] {
+    match language {
+        CodeLanguage::Rust => RUST_KEYWORDS,
+        CodeLanguage::Python => PYTHON_KEYWORDS,
+        CodeLanguage::C => C_KEYWORDS,
+        CodeLanguage::Shell => SHELL_KEYWORDS,
+    }
+}
+
+fn comment_start(language: CodeLanguage) -> &'static str {
+    match language {
+        CodeLanguage::Rust | CodeLanguage::C => "//",
+        CodeLanguage::Python | CodeLanguage::Shell => "#",
+    }
+}
+
+// rust uses single quotes for lifetimes, so only double quoted strings are highlighted
+fn is_quote(language: CodeLanguage, c: char) -> bool {
+    c == '"' || (c == '\'' && language != CodeLanguage::Rust)
+}
+
+/// the style of every character of a line of code: keywords, strings,
+/// numbers and comments get their own color
+pub fn highlight(line: &str, language: CodeLanguage) -> Vec<Style> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut styles = vec![Style::new(); chars.len()];
+    let keyword = Style::new().fg(Color::Rgb(190, 110, 220)).add_modifier(Modifier::BOLD);
+    let string = Style::new().fg(Color::Rgb(150, 200, 110));
+    let number = Style::new().fg(Color::Rgb(90, 180, 220));
+    let comment = Style::new().fg(Color::Rgb(128, 128, 128)).add_modifier(Modifier::ITALIC);
+    let comment_chars: Vec<char> = comment_start(language).chars().collect();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if chars[i..].starts_with(&comment_chars)
+            && !(language == CodeLanguage::Shell && i > 0 && chars[i - 1] == '$')
+        {
+            // the rest of the line is a comment
+            for style in &mut styles[i..] {
+                *style = comment;
+            }
+            break;
+        } else if is_quote(language, c) {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|q| *q == c)
+                .map_or(chars.len(), |p| i + p + 2);
+            for style in &mut styles[i..end] {
+                *style = string;
+            }
+            i = end;
+        } else if c.is_ascii_digit() {
+            styles[i] = number;
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' || c == '#' {
+            let end = chars[i..]
+                .iter()
+                .position(|w| !(w.is_alphanumeric() || *w == '_' || *w == '#'))
+                .map_or(chars.len(), |p| i + p);
+            let word: String = chars[i..end].iter().collect();
+            if keywords(language).contains(&word.as_str()) {
+                for style in &mut styles[i..end] {
+                    *style = keyword;
+                }
+            }
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    styles
+}
@@ -0,0 +1,199 @@
+use chrono::Duration;
+
+/// a single key press during a game. The keystrokes of a session are its log,
+/// every result of the session is computed from them
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+#[serde(default)]
+pub struct Keystroke {
+    // time since the start of the session, from the game's monotonic clock
+    #[serde(rename = "time_ms", with = "duration_ms")]
+    pub time: Duration,
+    // the character that should have been pressed
+    pub expected: char,
+    pub typed: char,
+    pub correct: bool,
+    // a correct key right after wrong ones at the same position
+    pub correction: bool,
+    // index of the unit (character, word, line) of the session the key was typed in
+    pub unit: usize,
+}
+
+// keystroke times are stored in milliseconds, like the other durations in the history
+mod duration_ms {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(time.num_milliseconds())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::milliseconds(i64::deserialize(deserializer)?))
+    }
+}
+
+/// the keystrokes of every unit of the session that was typed to the end
+pub fn finished_units(
+    keystrokes: &[Keystroke],
+    units_done: usize,
+) -> impl Iterator<Item = &[Keystroke]> {
+    keystrokes
+        .chunk_by(|a, b| a.unit == b.unit)
+        .filter(move |unit| unit[0].unit < units_done)
+}
+
+/// the text of every unit the keystrokes were typed in. Only the typed part of a
+/// unit is known, with the key that was missed last when a unit ends on a miss.
+pub fn typed_units(keystrokes: &[Keystroke]) -> Vec<String> {
+    keystrokes
+        .chunk_by(|a, b| a.unit == b.unit)
+        .map(|unit| {
+            let mut text: String = unit.iter().filter(|k| k.correct).map(|k| k.expected).collect();
+            if let Some(last) = unit.last().filter(|k| !k.correct) {
+                text.push(last.expected);
+            }
+            text
+        })
+        .collect()
+}
+
+/// finished units that were typed without a wrong key
+pub fn units_correct(keystrokes: &[Keystroke], units_done: usize) -> u32 {
+    finished_units(keystrokes, units_done)
+        .filter(|unit| unit.iter().all(|k| k.correct))
+        .count() as u32
+}
+
+/// the transition times of every finished unit, one n-gram per unit.
+/// The space typed after the n-gram is not part of it.
+pub fn ngram_results(keystrokes: &[Keystroke], units_done: usize) -> Vec<NgramResult> {
+    let mut results: Vec<NgramResult> = vec![];
+    for unit in finished_units(keystrokes, units_done) {
+        let unit: Vec<&Keystroke> = unit.iter().filter(|k| k.expected != ' ').collect();
+        let hits: Vec<&Keystroke> = unit.iter().copied().filter(|k| k.correct).collect();
+        let (Some(first), Some(last)) = (hits.first(), hits.last()) else {
+            continue;
+        };
+        let ngram: String = hits.iter().map(|k| k.expected).collect();
+        let missed = hits.len() < unit.len();
+        record_ngram(&mut results, &ngram, last.time - first.time, missed);
+    }
+    results
+}
+
+/// how long the transitions within an n-gram took, over every time it was typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct NgramResult {
+    pub ngram: String,
+    pub count: u32,
+    pub misses: u32,
+    // time from the first to the last key of the n-gram, summed over `count`
+    pub total_transition_ms: i64,
+}
+
+impl NgramResult {
+    pub fn mean_transition_ms(&self) -> f64 {
+        self.total_transition_ms as f64 / f64::from(self.count.max(1))
+    }
+}
+
+/// adds one typed n-gram to the results
+fn record_ngram(
+    results: &mut Vec<NgramResult>,
+    ngram: &str,
+    transition: Duration,
+    missed: bool,
+) {
+    let index = match results.iter().position(|r| r.ngram == ngram) {
+        Some(index) => index,
+        None => {
+            results.push(NgramResult {
+                ngram: ngram.to_string(),
+                count: 0,
+                misses: 0,
+                total_transition_ms: 0,
+            });
+            results.len() - 1
+        }
+    };
+    results[index].count += 1;
+    results[index].total_transition_ms += transition.num_milliseconds();
+    if missed {
+        results[index].misses += 1;
+    }
+}
+
+/// speed and accuracy of a session, computed from its keystrokes
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+#[serde(default)]
+pub struct Metrics {
+    /// words per minute counting every keystroke, with a word being five characters
+    pub gross_wpm: f64,
+    /// gross wpm minus one word per wrong keystroke per minute
+    pub net_wpm: f64,
+    /// correctly typed characters per minute
+    pub cpm: f64,
+    /// percentage of keystrokes that were correct
+    pub raw_accuracy: f64,
+    /// percentage of target characters typed right on the first attempt,
+    /// a character that needed several attempts counts as one miss
+    pub corrected_accuracy: f64,
+    /// 100 for perfectly even keystroke rhythm, lower the more the intervals vary
+    pub consistency: f64,
+}
+
+impl Metrics {
+    pub fn from_keystrokes(keystrokes: &[Keystroke], elapsed: Duration) -> Metrics {
+        let minutes = elapsed.num_milliseconds() as f64 / 60_000.0;
+        if keystrokes.is_empty() || minutes <= 0.0 {
+            return Metrics::default();
+        }
+
+        let total = keystrokes.len() as f64;
+        let correct = keystrokes.iter().filter(|k| k.correct).count() as f64;
+        let errors = total - correct;
+
+        // a target character that needed several attempts ends with a correction
+        let first_try = keystrokes
+            .iter()
+            .filter(|k| k.correct && !k.correction)
+            .count();
+
+        let gross_wpm = total / 5.0 / minutes;
+        Metrics {
+            gross_wpm,
+            net_wpm: (gross_wpm - errors / minutes).max(0.0),
+            cpm: correct / minutes,
+            raw_accuracy: 100.0 * correct / total,
+            corrected_accuracy: if correct > 0.0 {
+                100.0 * first_try as f64 / correct
+            } else {
+                0.0
+            },
+            consistency: consistency(keystrokes),
+        }
+    }
+}
+
+/// 100 minus the coefficient of variation (in percent) of the time between keystrokes
+fn consistency(keystrokes: &[Keystroke]) -> f64 {
+    let intervals: Vec<f64> = keystrokes
+        .windows(2)
+        .map(|pair| (pair[1].time - pair[0].time).num_milliseconds() as f64)
+        .collect();
+    if intervals.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    let variance =
+        intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / intervals.len() as f64;
+    (100.0 - 100.0 * variance.sqrt() / mean).clamp(0.0, 100.0)
+}
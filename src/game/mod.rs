@@ -1 +1,5 @@
+pub mod clock;
 pub mod game_page;
+pub mod ghost;
+pub mod highlight;
+pub mod metrics;
@@ -0,0 +1,180 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, TimeZone};
+
+use crate::game::metrics::{Keystroke, Metrics};
+use crate::settings::settings_struct::GameMode;
+
+static APP_NAME: &str = "crabtype";
+static CONFIG_NAME: &str = "config";
+static HISTORY_FILE: &str = "history.jsonl";
+
+/// schema version written with every new record, bump it when the record changes
+pub const HISTORY_VERSION: u32 = 3;
+
+/// accuracy and speed for a single key within one session
+#[derive(Debug, Clone, Default, serde_derive::Serialize, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct KeyResult {
+    pub key: char,
+    pub hits: u32,
+    pub misses: u32,
+    // summed time between the previous keystroke and the correct press of this key
+    pub total_latency_ms: i64,
+}
+
+impl KeyResult {
+    pub fn error_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.misses as f64 / total as f64
+        }
+    }
+
+    pub fn mean_latency_ms(&self) -> f64 {
+        if self.hits == 0 {
+            0.0
+        } else {
+            self.total_latency_ms as f64 / self.hits as f64
+        }
+    }
+
+    pub fn merge(&mut self, other: &KeyResult) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.total_latency_ms += other.total_latency_ms;
+    }
+}
+
+/// one finished game, as stored in the history file
+#[derive(Debug, Clone, serde_derive::Serialize, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct SessionRecord {
+    pub version: u32,
+    // seconds since the unix epoch
+    pub timestamp: i64,
+    pub mode: GameMode,
+    // added in version 2
+    pub hardcore: bool,
+    pub character_sets: Vec<String>,
+    pub duration_ms: i64,
+    pub metrics: Metrics,
+    pub key_results: Vec<KeyResult>,
+    // added in version 3, the log the results above were computed from
+    pub keystrokes: Vec<Keystroke>,
+}
+
+impl Default for SessionRecord {
+    fn default() -> SessionRecord {
+        SessionRecord {
+            version: HISTORY_VERSION,
+            timestamp: 0,
+            mode: GameMode::Characters,
+            hardcore: false,
+            character_sets: vec![],
+            duration_ms: 0,
+            metrics: Metrics::default(),
+            key_results: vec![],
+            keystrokes: vec![],
+        }
+    }
+}
+
+impl SessionRecord {
+    pub fn local_time(&self) -> DateTime<Local> {
+        Local
+            .timestamp_opt(self.timestamp, 0)
+            .single()
+            .unwrap_or_default()
+    }
+
+    /// enabled character sets joined for display and filtering, e.g. "letters+numbers"
+    pub fn character_set_label(&self) -> String {
+        self.character_sets.join("+")
+    }
+}
+
+/// aggregates keystrokes per expected key
+pub fn key_results(keystrokes: &[Keystroke]) -> Vec<KeyResult> {
+    let mut results: Vec<KeyResult> = vec![];
+    let mut previous = None;
+    for key in keystrokes {
+        let index = match results.iter().position(|r| r.key == key.expected) {
+            Some(index) => index,
+            None => {
+                results.push(KeyResult {
+                    key: key.expected,
+                    ..KeyResult::default()
+                });
+                results.len() - 1
+            }
+        };
+        if key.correct {
+            results[index].hits += 1;
+            if let Some(previous) = previous {
+                results[index].total_latency_ms += (key.time - previous).num_milliseconds();
+            }
+        } else {
+            results[index].misses += 1;
+        }
+        previous = Some(key.time);
+    }
+    results.sort_by_key(|r| r.key);
+    results
+}
+
+/// sums the per key results of several sessions
+pub fn aggregate_key_results<'a, I>(sessions: I) -> Vec<KeyResult>
+where
+    I: IntoIterator<Item = &'a SessionRecord>,
+{
+    let mut results: Vec<KeyResult> = vec![];
+    for session in sessions {
+        for key_result in &session.key_results {
+            match results.iter_mut().find(|r| r.key == key_result.key) {
+                Some(result) => result.merge(key_result),
+                None => results.push(key_result.clone()),
+            }
+        }
+    }
+    results.sort_by_key(|r| r.key);
+    results
+}
+
+/// the history file lives next to the confy config
+pub fn history_path() -> Result<PathBuf, confy::ConfyError> {
+    let config_path = confy::get_configuration_file_path(APP_NAME, CONFIG_NAME)?;
+    Ok(config_path.with_file_name(HISTORY_FILE))
+}
+
+/// appends a record as one json line to the history file
+pub fn append_session(record: &SessionRecord) -> io::Result<()> {
+    let path = history_path().map_err(io::Error::other)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(record)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+/// reads every record from the history file, oldest first.
+/// Lines that can not be parsed are skipped, and fields missing from
+/// records written by older versions get their default value.
+pub fn load_sessions() -> Vec<SessionRecord> {
+    let Ok(path) = history_path() else {
+        return vec![];
+    };
+    let Ok(content) = fs::read_to_string(path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<SessionRecord>(line).ok())
+        .collect()
+}
@@ -0,0 +1,131 @@
+use crate::char_lib::layouts::KeyboardLayout;
+use crate::game::metrics::Keystroke;
+use crate::history::session_history;
+
+static APP_NAME: &str = "crabtype";
+static LESSON_NAME: &str = "lessons";
+
+/// the (row, column) of the keys in the order they are unlocked, starting from
+/// the home row. Keys that are not letters on the chosen layout are skipped.
+static LESSON_POSITIONS: [(usize, usize); 33] = [
+    (2, 3), (2, 6), (2, 2), (2, 7), (2, 1), (2, 8), (2, 0), (2, 9), (2, 4), (2, 5),
+    (1, 3), (1, 6), (1, 2), (1, 7), (1, 1), (1, 8), (1, 0), (1, 9), (1, 4), (1, 5),
+    (3, 3), (3, 6), (3, 4), (3, 5), (3, 2), (3, 7), (3, 1), (3, 8), (3, 0), (3, 9),
+    (2, 10), (1, 10), (1, 11),
+];
+/// number of keys unlocked from the start
+pub static START_KEYS: usize = 6;
+// weight of the newest session in the smoothed speed and accuracy of a key
+static SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct LessonKey {
+    pub key: char,
+    // smoothed over the sessions the key was practiced in
+    pub cpm: f64,
+    pub accuracy: f64,
+    pub sessions: u32,
+}
+
+#[derive(Debug, Clone, serde_derive::Serialize, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct LessonProgress {
+    pub unlocked: usize,
+    pub keys: Vec<LessonKey>,
+}
+
+impl Default for LessonProgress {
+    fn default() -> LessonProgress {
+        LessonProgress {
+            unlocked: START_KEYS,
+            keys: vec![],
+        }
+    }
+}
+
+/// the letters of a layout in the order they are unlocked
+pub fn lesson_order(layout: &KeyboardLayout) -> Vec<char> {
+    LESSON_POSITIONS
+        .iter()
+        .filter_map(|(row, column)| layout.key_at(*row, *column))
+        .filter(|key| key.is_alphabetic())
+        .collect()
+}
+
+impl LessonProgress {
+    pub fn read() -> Result<LessonProgress, confy::ConfyError> {
+        confy::load(APP_NAME, LESSON_NAME)
+    }
+
+    pub fn write(&self) -> Result<(), confy::ConfyError> {
+        confy::store(APP_NAME, LESSON_NAME, self)
+    }
+
+    pub fn unlocked_keys(&self, layout: &KeyboardLayout) -> Vec<char> {
+        lesson_order(layout).into_iter().take(self.unlocked).collect()
+    }
+
+    /// the most recently unlocked key, which is practiced more often
+    pub fn focus_key(&self, layout: &KeyboardLayout) -> Option<char> {
+        self.unlocked_keys(layout).last().copied()
+    }
+
+    pub fn key(&self, key: char) -> Option<&LessonKey> {
+        self.keys.iter().find(|k| k.key == key)
+    }
+
+    /// how close a key is to the target, 1.0 when both speed and accuracy are met
+    pub fn key_progress(&self, key: char, target_cpm: u16, target_accuracy: u8) -> f64 {
+        match self.key(key) {
+            Some(k) => {
+                let speed = k.cpm / f64::from(target_cpm.max(1));
+                let accuracy = k.accuracy / f64::from(target_accuracy.max(1));
+                speed.min(accuracy).clamp(0.0, 1.0)
+            }
+            None => 0.0,
+        }
+    }
+
+    /// folds a finished session into the per key stats, and unlocks the
+    /// next key once every unlocked key has reached the target.
+    /// Returns the newly unlocked key, if any.
+    pub fn update(
+        &mut self,
+        keystrokes: &[Keystroke],
+        layout: &KeyboardLayout,
+        target_cpm: u16,
+        target_accuracy: u8,
+    ) -> Option<char> {
+        for result in session_history::key_results(keystrokes) {
+            let cpm = if result.hits > 0 && result.total_latency_ms > 0 {
+                60_000.0 * f64::from(result.hits) / result.total_latency_ms as f64
+            } else {
+                0.0
+            };
+            let accuracy = 100.0 * (1.0 - result.error_rate());
+            match self.keys.iter_mut().find(|k| k.key == result.key) {
+                Some(key) => {
+                    key.cpm = SMOOTHING * cpm + (1.0 - SMOOTHING) * key.cpm;
+                    key.accuracy = SMOOTHING * accuracy + (1.0 - SMOOTHING) * key.accuracy;
+                    key.sessions += 1;
+                }
+                None => self.keys.push(LessonKey {
+                    key: result.key,
+                    cpm,
+                    accuracy,
+                    sessions: 1,
+                }),
+            }
+        }
+
+        let all_met = self
+            .unlocked_keys(layout)
+            .iter()
+            .all(|k| self.key_progress(*k, target_cpm, target_accuracy) >= 1.0);
+        if all_met && self.unlocked < lesson_order(layout).len() {
+            self.unlocked += 1;
+            return self.focus_key(layout);
+        }
+        None
+    }
+}
@@ -0,0 +1 @@
+pub mod lesson_progress;
@@ -1,21 +1,63 @@
+mod books;
 mod char_lib;
+mod cli;
+mod error;
 mod game;
+mod history;
+mod lessons;
 mod menu;
+mod notice;
+mod replay;
 mod settings;
+mod stats;
 mod tui;
 
-fn main() -> Result<(), std::io::Error> {
+use std::io;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+
+use cli::args::{Cli, Command, PlayArgs};
+use cli::commands;
+use settings::settings_struct::Settings;
+use tui::screen::Screen;
+
+/// opens the app on `screen`, after reading the text given on the command line
+fn run_app(screen: Screen, args: PlayArgs) -> color_eyre::Result<()> {
+    // a broken config is reported inside the app
+    let mut settings = Settings::read_config().unwrap_or_default();
+    args.apply(&mut settings);
+    let book = commands::shelve_book(&args, &settings)?;
+    if let Some(book) = book.as_ref().filter(|book| book.is_finished()) {
+        let message = format!("{} has been typed to the end", book.name);
+        Cli::command().error(ErrorKind::ValueValidation, message).exit();
+    }
+    // only the menu and games play a text, other pages leave stdin alone
+    let text = match screen {
+        Screen::Menu | Screen::Game => commands::custom_text(&args, &settings)?,
+        _ => None,
+    };
+
     let mut terminal = tui::tui_tools::init()?;
-    tui::pages::App::default().run(&mut terminal)?;
-    tui::tui_tools::restore()
+    tui::pages::App::new(screen, args, text, book).run(&mut terminal)?;
+    tui::tui_tools::restore()?;
+    Ok(())
 }
 
-// mod docs_example;
-// use color_eyre::{eyre::Ok, Result};
-// fn run_docs_example() -> Result<()> {
-//     docs_example::errors::install_hooks()?;
-//     let mut terminal = docs_example::tui::init()?;
-//     docs_example::app::App::default().run(&mut terminal)?;
-//     docs_example::tui::restore()?;s
-//     Ok(())
-// }
+fn main() -> color_eyre::Result<()> {
+    tui::errors::install_hooks()?;
+    let cli = Cli::parse();
+    match cli.command {
+        None => run_app(Screen::Menu, cli.play),
+        Some(Command::Play(args)) => run_app(Screen::Game, args),
+        Some(Command::Stats) => run_app(Screen::Stats, PlayArgs::default()),
+        Some(Command::Config { action }) => match commands::config(action) {
+            // mistakes in the key or value read like the other command line errors
+            Err(error) if error.kind() == io::ErrorKind::InvalidInput => {
+                Cli::command().error(ErrorKind::InvalidValue, error).exit()
+            }
+            result => Ok(result?),
+        },
+        Some(Command::Lessons) => Ok(commands::lessons()?),
+    }
+}
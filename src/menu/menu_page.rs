@@ -1,22 +1,74 @@
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     prelude::*,
     symbols::border,
     widgets::{block::*, *},
 };
 
-pub fn render(area: Rect, buf: &mut Buffer) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .style(
-            Style::default()
-                .fg(Color::Blue)
-                .bg(Color::Black)
-                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
-        )
-        .border_set(border::THICK);
-
-    let crabtype: String = {
-        "
+use crate::books::bookshelf::{Book, Bookshelf};
+use crate::error::AppError;
+use crate::tui::screen::{Action, Page, Screen};
+
+/// the menu, with the books in progress that can be resumed with their number
+#[derive(Debug, Default)]
+pub struct MenuPage {
+    pub books: Vec<Book>,
+    // a bookshelf that could not be read, for the notice page
+    pub errors: Vec<AppError>,
+}
+
+impl MenuPage {
+    /// reads the books in progress from the bookshelf
+    pub fn load() -> MenuPage {
+        match Bookshelf::read() {
+            Ok(shelf) => MenuPage {
+                books: shelf.in_progress(),
+                errors: vec![],
+            },
+            Err(error) => MenuPage {
+                books: vec![],
+                errors: vec![AppError::BadSaveFile("bookshelf", error)],
+            },
+        }
+    }
+}
+
+impl Page for MenuPage {
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Action {
+        match key_event.code {
+            KeyCode::Char('q') => Action::Quit,
+            KeyCode::Char('s') => Action::Push(Screen::Settings),
+            KeyCode::Char('t') => Action::Push(Screen::Stats),
+            KeyCode::Char('r') => Action::Push(Screen::Replay),
+            KeyCode::Char('b') => Action::StartGame(None),
+            KeyCode::Char(number @ '1'..='9') => {
+                let index = number as usize - '1' as usize;
+                match self.books.get(index) {
+                    Some(book) => Action::StartGame(Some(book.clone())),
+                    None => Action::None,
+                }
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn take_errors(&mut self) -> Vec<AppError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(
+                Style::default()
+                    .fg(Color::Blue)
+                    .bg(Color::Black)
+                    .add_modifier(Modifier::ITALIC | Modifier::BOLD),
+            )
+            .border_set(border::THICK);
+
+        let crabtype: String = {
+            "
                                                                        
                                                                        
        ████████                 ████████████████                   
@@ -29,18 +81,27 @@ pub fn render(area: Rect, buf: &mut Buffer) {
                                                                        
                                                                        
 "
-        .to_string()
-    };
+            .to_string()
+        };
 
-    let menu_options: String = {
-        "
+        let menu_options: String = {
+            "
     [b]egin
     [s]ettings
+    [t]stats
+    [r]eplays
     [q]uit
         "
-        .to_string()
-    };
-    let comb_str = crabtype + &menu_options;
+            .to_string()
+        };
+        let mut comb_str = crabtype + &menu_options;
+        if !self.books.is_empty() {
+            comb_str += "\n    Books in progress:\n";
+            for (i, book) in self.books.iter().take(9).enumerate() {
+                comb_str += &format!("    [{}] {} ({:.1}%)\n", i + 1, book.name, book.progress());
+            }
+        }
 
-    Paragraph::new(comb_str).block(block).render(area, buf);
+        Paragraph::new(comb_str).block(block).render(area, buf);
+    }
 }
@@ -0,0 +1 @@
+pub mod notice_page;
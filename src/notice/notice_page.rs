@@ -0,0 +1,96 @@
+use std::error::Error;
+
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+use crate::error::AppError;
+use crate::tui::screen::{Action, Page};
+
+/// problems the app recovered from, shown over the current page one at a
+/// time and dismissed with any key
+#[derive(Debug, Default)]
+pub struct NoticePage {
+    pub notices: Vec<AppError>,
+}
+
+impl Page for NoticePage {
+    fn handle_key_event(&mut self, _key_event: KeyEvent) -> Action {
+        if !self.notices.is_empty() {
+            self.notices.remove(0);
+        }
+        if self.notices.is_empty() {
+            Action::Pop
+        } else {
+            Action::None
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(notice) = self.notices.first() else {
+            return;
+        };
+        let title = Title::from(" Something went wrong ".bold());
+        let instructions = Title::from(Line::from(vec![" Continue ".into(), "<any key> ".bold()]));
+        let block = Block::default()
+            .title(title.alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Red).bg(Color::Black))
+            .border_set(border::THICK);
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(notice.to_string()).bold(),
+        ];
+        // the causes, like the position of a syntax error in the config
+        let mut source = notice.source();
+        while let Some(cause) = source {
+            lines.extend(
+                cause
+                    .to_string()
+                    .lines()
+                    .map(|line| Line::from(line.to_string()).alignment(Alignment::Left)),
+            );
+            source = cause.source();
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(notice.hint()).fg(Color::Gray));
+        if self.notices.len() > 1 {
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!("{} more", self.notices.len() - 1)).fg(Color::Gray));
+        }
+
+        let popup = popup_area(area);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .centered()
+            .block(block)
+            .render(popup, buf);
+    }
+}
+
+// the middle of the screen, leaving the page below visible around it
+fn popup_area(area: Rect) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+    ])
+    .areas(area);
+    let [_, popup, _] = Layout::horizontal([
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+    ])
+    .areas(middle);
+    popup
+}
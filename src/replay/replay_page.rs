@@ -0,0 +1,338 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+use chrono::Duration;
+
+use crate::error::AppError;
+use crate::game::clock::{Clock, FakeClock, MonotonicClock};
+use crate::game::game_page::{GameEnd, GameLogic};
+use crate::game::metrics;
+use crate::history::session_history::{self, SessionRecord};
+use crate::settings::settings_struct::Settings;
+use crate::tui::screen::{Action, Page};
+
+/// past sessions with a keystroke log, and the one being replayed
+#[derive(Debug, Default)]
+pub struct ReplayPage {
+    // newest first
+    pub sessions: Vec<SessionRecord>,
+    pub state: TableState,
+    // the settings the replayed game is drawn with
+    pub settings: Settings,
+    pub player: Option<Player>,
+}
+
+/// plays the keystrokes of a session into a game, in real time or key by key
+#[derive(Debug)]
+pub struct Player {
+    pub session: SessionRecord,
+    pub game: GameLogic,
+    // the replayed game's time, moved to every keystroke before it is typed
+    pub clock: FakeClock,
+    // keystrokes of the session typed into `game` so far
+    pub next: usize,
+    pub speed: i32,
+    pub paused: bool,
+    wall_clock: MonotonicClock,
+    last_tick: Duration,
+}
+
+impl Player {
+    /// a game showing the session before its first keystroke
+    fn new(session: SessionRecord, settings: &Settings) -> Result<Player, AppError> {
+        let clock = FakeClock::default();
+        let settings = Settings {
+            game_mode: session.mode,
+            hardcore: false,
+            adaptive: false,
+            ..settings.clone()
+        };
+        let mut game = GameLogic::new(settings, Box::new(clock.clone()))?;
+        // the typed part of the last unit may not be all of it, the
+        // placeholder keeps the game from ending on its last key
+        let mut units = metrics::typed_units(&session.keystrokes);
+        units.push("…".to_string());
+        game.use_replay(units);
+        game.reset()?;
+        let wall_clock = MonotonicClock::default();
+        Ok(Player {
+            last_tick: wall_clock.now(),
+            wall_clock,
+            session,
+            game,
+            clock,
+            next: 0,
+            speed: 1,
+            paused: false,
+        })
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::milliseconds(self.session.duration_ms)
+    }
+
+    fn is_finished(&self) -> bool {
+        !self.game.play
+    }
+
+    /// types the next keystroke of the session
+    fn step(&mut self) {
+        let Some(key) = self.session.keystrokes.get(self.next) else {
+            return self.finish();
+        };
+        self.clock.set(key.time);
+        self.game.get_time();
+        self.game.compare_pressed_char(key.typed);
+        self.next += 1;
+    }
+
+    /// types every keystroke up to `time`, the session ends at its duration
+    fn play_until(&mut self, time: Duration) {
+        let time = time.min(self.duration());
+        while self
+            .session
+            .keystrokes
+            .get(self.next)
+            .is_some_and(|key| key.time <= time)
+            && !self.is_finished()
+        {
+            self.step();
+        }
+        self.clock.set(time);
+        self.game.get_time();
+        if self.next >= self.session.keystrokes.len() && time >= self.duration() {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.is_finished() {
+            return;
+        }
+        let end = if self.session.mode.is_text_based() {
+            GameEnd::TextFinished
+        } else {
+            GameEnd::TimeUp
+        };
+        self.game.finish(end);
+    }
+
+    fn tick(&mut self) {
+        let now = self.wall_clock.now();
+        let elapsed = now - self.last_tick;
+        self.last_tick = now;
+        if !self.paused && !self.is_finished() {
+            self.play_until(self.clock.now() + elapsed * self.speed);
+        }
+    }
+}
+
+impl ReplayPage {
+    /// reads the sessions that have a keystroke log from the history
+    pub fn load(settings: Settings) -> ReplayPage {
+        let mut sessions: Vec<SessionRecord> = session_history::load_sessions()
+            .into_iter()
+            .filter(|s| !s.keystrokes.is_empty())
+            .collect();
+        sessions.reverse();
+        let mut state = TableState::default();
+        if !sessions.is_empty() {
+            state.select(Some(0));
+        }
+        ReplayPage {
+            sessions,
+            state,
+            settings,
+            player: None,
+        }
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.sessions.is_empty() {
+            return;
+        }
+        let len = self.sessions.len() as isize;
+        let i = self.state.selected().unwrap_or(0) as isize;
+        self.state.select(Some((i + offset).rem_euclid(len) as usize));
+    }
+
+    /// starts the selected session from its first key
+    fn play(&mut self) -> Action {
+        let Some(session) = self.state.selected().and_then(|i| self.sessions.get(i)) else {
+            return Action::None;
+        };
+        match Player::new(session.clone(), &self.settings) {
+            Ok(player) => {
+                self.player = Some(player);
+                Action::None
+            }
+            Err(error) => Action::Notify(error),
+        }
+    }
+
+    /// replays the current session again up to keystroke `next`, paused
+    fn seek(&mut self, next: usize) -> Action {
+        let Some(player) = &self.player else {
+            return Action::None;
+        };
+        let speed = player.speed;
+        match Player::new(player.session.clone(), &self.settings) {
+            Ok(mut player) => {
+                for _ in 0..next {
+                    player.step();
+                }
+                player.paused = true;
+                player.speed = speed;
+                self.player = Some(player);
+                Action::None
+            }
+            Err(error) => Action::Notify(error),
+        }
+    }
+
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let instructions = Title::from(Line::from(vec![
+            " back: <esc> | down: ↓ or j | up: ↑ or k | replay: <enter> ".into(),
+        ]));
+        let block = Block::default()
+            .title(Title::from(" Replays ".bold()).alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Blue).bg(Color::Black))
+            .border_set(border::THICK);
+
+        if self.sessions.is_empty() {
+            Paragraph::new("No sessions with a keystroke log yet, play a game first.")
+                .centered()
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let rows: Vec<Row> = self
+            .sessions
+            .iter()
+            .map(|s| {
+                Row::new(vec![
+                    s.local_time().format("%d/%m/%Y %H:%M").to_string(),
+                    s.mode.name().to_string(),
+                    s.character_set_label(),
+                    format!("{:.1}", s.metrics.net_wpm),
+                    format!("{:.1}%", s.metrics.raw_accuracy),
+                    format!("{:.1}s", s.duration_ms as f64 / 1000.0),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(17),
+                Constraint::Length(11),
+                Constraint::Min(10),
+                Constraint::Length(8),
+                Constraint::Length(9),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec!["Date", "Mode", "Character sets", "Net WPM", "Accuracy", "Time"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">");
+        StatefulWidget::render(table, area, buf, &mut self.state);
+    }
+}
+
+impl Page for ReplayPage {
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Action {
+        let Some(player) = &mut self.player else {
+            return match key_event.code {
+                KeyCode::Esc => Action::Pop,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.select(1);
+                    Action::None
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.select(-1);
+                    Action::None
+                }
+                KeyCode::Enter => self.play(),
+                _ => Action::None,
+            };
+        };
+        match key_event.code {
+            KeyCode::Esc => self.player = None,
+            KeyCode::Char(' ') => player.paused = !player.paused,
+            KeyCode::Char(speed @ ('1' | '2' | '4')) => {
+                player.speed = speed.to_digit(10).map_or(1, |s| s as i32);
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                player.paused = true;
+                player.step();
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                let previous = player.next.saturating_sub(1);
+                return self.seek(previous);
+            }
+            KeyCode::Char('r') => return self.seek(0),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn tick(&mut self) {
+        if let Some(player) = &mut self.player {
+            player.tick();
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(player) = &self.player else {
+            return self.render_list(area, buf);
+        };
+        let state = if player.is_finished() {
+            "finished".to_string()
+        } else if player.paused {
+            "paused".to_string()
+        } else {
+            format!("{}x", player.speed)
+        };
+        let title = Title::from(
+            format!(
+                " Replay {} | key {}/{} | {state} ",
+                player.session.local_time().format("%d/%m/%Y %H:%M"),
+                player.next,
+                player.session.keystrokes.len()
+            )
+            .bold(),
+        );
+        let instructions = Title::from(Line::from(vec![
+            " back: <esc> | pause: <space> | speed: 1 2 4 | step: ← → or h l | restart: r ".into(),
+        ]));
+        let block = Block::default()
+            .title(title.alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Magenta).bg(Color::Black))
+            .border_set(border::THICK);
+        if player.is_finished() {
+            player.game.render_result(area, buf, block);
+        } else {
+            player.game.render_game(area, buf, block);
+        }
+    }
+}
@@ -1,17 +1,27 @@
-use std::usize;
-
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     prelude::*,
     symbols::border,
     widgets::{block::*, *},
 };
 
-use super::settings_struct;
+use super::settings_struct::{self, CodeLanguage, GameMode, NgramList, QuoteLength, WordList};
+use crate::char_lib::{char_sets, layouts};
+use crate::tui::screen::{Action, Page};
+
+// reference name of a custom character set item, followed by the set id
+static CUSTOM_SET_PREFIX: &str = "char_set:";
 
 #[derive(Debug)]
 pub enum SettingsStatus {
     Boolean(bool),
-    Uint(u8),
+    Uint(u16),
+    Choice(usize, Vec<String>),
+}
+
+/// a number setting stored in a byte, its range keeps it below 256
+fn byte(val: u16) -> u8 {
+    u8::try_from(val).unwrap_or(u8::MAX)
 }
 
 #[derive(Debug)]
@@ -28,27 +38,30 @@ pub struct SettingsStateList {
     pub state: TableState,
     pub items: Vec<SettingsItem>,
     pub last_selected: Option<usize>,
-    pub settings_struct: settings_struct::Settings,
 }
 
 impl Default for SettingsStateList {
     fn default() -> Self {
-        let settings = settings_struct::Settings::read_config().unwrap();
+        let settings = settings_struct::Settings::read_config().unwrap_or_default();
+        let layout_names: Vec<String> = layouts::available_layouts()
+            .into_iter()
+            .map(|layout| layout.name)
+            .collect();
 
-        let loaded_items = vec![
+        let mut loaded_items = vec![
             SettingsItem {
                 description: "Total game time (sec)".to_string(),
-                status: SettingsStatus::Uint(settings.total_time_sec),
+                status: SettingsStatus::Uint(settings.total_time_sec.into()),
                 reference_name: "total_time_sec".to_string(),
             },
             SettingsItem {
                 description: "History Length".to_string(),
-                status: SettingsStatus::Uint(settings.history_length),
+                status: SettingsStatus::Uint(settings.history_length.into()),
                 reference_name: "history_length".to_string(),
             },
             SettingsItem {
                 description: "Future Length".to_string(),
-                status: SettingsStatus::Uint(settings.future_length),
+                status: SettingsStatus::Uint(settings.future_length.into()),
                 reference_name: "future_length".to_string(),
             },
             SettingsItem {
@@ -56,6 +69,98 @@ impl Default for SettingsStateList {
                 status: SettingsStatus::Boolean(settings.large_char),
                 reference_name: "large_char".to_string(),
             },
+            SettingsItem {
+                description: "Show key heatmap on result screen".to_string(),
+                status: SettingsStatus::Boolean(settings.result_heatmap),
+                reference_name: "result_heatmap".to_string(),
+            },
+            SettingsItem {
+                description: "Game mode".to_string(),
+                status: SettingsStatus::Choice(
+                    GameMode::SELECTABLE
+                        .iter()
+                        .position(|mode| *mode == settings.game_mode)
+                        .unwrap_or(0),
+                    GameMode::SELECTABLE.iter().map(|mode| mode.name().to_string()).collect(),
+                ),
+                reference_name: "game_mode".to_string(),
+            },
+            SettingsItem {
+                description: "Word list (word mode)".to_string(),
+                status: SettingsStatus::Choice(
+                    WordList::ALL
+                        .iter()
+                        .position(|list| *list == settings.word_list)
+                        .unwrap_or(0),
+                    WordList::ALL.iter().map(|list| list.name().to_string()).collect(),
+                ),
+                reference_name: "word_list".to_string(),
+            },
+            SettingsItem {
+                description: "Quote length (quote mode)".to_string(),
+                status: SettingsStatus::Choice(
+                    QuoteLength::ALL
+                        .iter()
+                        .position(|length| *length == settings.quote_length)
+                        .unwrap_or(0),
+                    QuoteLength::ALL.iter().map(|length| length.name().to_string()).collect(),
+                ),
+                reference_name: "quote_length".to_string(),
+            },
+            SettingsItem {
+                description: "N-gram list (n-gram mode)".to_string(),
+                status: SettingsStatus::Choice(
+                    NgramList::ALL
+                        .iter()
+                        .position(|list| *list == settings.ngram_list)
+                        .unwrap_or(0),
+                    NgramList::ALL.iter().map(|list| list.name().to_string()).collect(),
+                ),
+                reference_name: "ngram_list".to_string(),
+            },
+            SettingsItem {
+                description: "Language (code mode)".to_string(),
+                status: SettingsStatus::Choice(
+                    CodeLanguage::ALL
+                        .iter()
+                        .position(|language| *language == settings.code_language)
+                        .unwrap_or(0),
+                    CodeLanguage::ALL.iter().map(|language| language.name().to_string()).collect(),
+                ),
+                reference_name: "code_language".to_string(),
+            },
+            SettingsItem {
+                description: "Keyboard layout".to_string(),
+                status: SettingsStatus::Choice(
+                    layout_names
+                        .iter()
+                        .position(|name| *name == settings.layout)
+                        .unwrap_or(0),
+                    layout_names.clone(),
+                ),
+                reference_name: "layout".to_string(),
+            },
+            SettingsItem {
+                description: "Emulate layout from OS layout".to_string(),
+                status: SettingsStatus::Choice(
+                    layout_names
+                        .iter()
+                        .position(|name| Some(name) == settings.emulate_from.as_ref())
+                        .map_or(0, |i| i + 1),
+                    ["Off".to_string()].into_iter().chain(layout_names.clone()).collect(),
+                ),
+                reference_name: "emulate_from".to_string(),
+            },
+            SettingsItem {
+                description: "Lesson target speed (char/min)".to_string(),
+                status: SettingsStatus::Uint(settings.lesson_target_cpm),
+                reference_name: "lesson_target_cpm".to_string(),
+            },
+            SettingsItem {
+                description: "Lesson target accuracy (%)".to_string(),
+                status: SettingsStatus::Uint(settings.lesson_target_accuracy.into()),
+                reference_name: "lesson_target_accuracy".to_string(),
+            },
             SettingsItem {
                 description: "Include lower case Letters".to_string(),
                 status: SettingsStatus::Boolean(settings.lower_case_letters),
@@ -78,21 +183,65 @@ impl Default for SettingsStateList {
             },
             SettingsItem {
                 description: "Included special characters".to_string(),
-                status: SettingsStatus::Boolean(settings.parenthesis),
+                status: SettingsStatus::Boolean(settings.special_characters),
                 reference_name: "special_characters".to_string(),
             },
-            // SettingsItem {
-            //     description: "Indicate for 10 finger typing".to_string(),
-            //     status: SettingsStatus::Boolean(settings.ten_finger_typing),
-            //     reference_name: "ten_finger_typing".to_string(),
-            // },
-            // SettingsItem {
-            //     description: "Hardcore mode".to_string(),
-            //     status: SettingsStatus::Boolean(settings.hardcore),
-            //     reference_name: "hardcore".to_string(),
-            // },
+            SettingsItem {
+                description: "Practice weak characters more often".to_string(),
+                status: SettingsStatus::Boolean(settings.adaptive),
+                reference_name: "adaptive".to_string(),
+            },
+            SettingsItem {
+                description: "Indicate for 10 finger typing".to_string(),
+                status: SettingsStatus::Boolean(settings.ten_finger_typing),
+                reference_name: "ten_finger_typing".to_string(),
+            },
+            SettingsItem {
+                description: "Hardcore mode".to_string(),
+                status: SettingsStatus::Boolean(settings.hardcore),
+                reference_name: "hardcore".to_string(),
+            },
+            SettingsItem {
+                description: "Hardcore starting speed (char/min)".to_string(),
+                status: SettingsStatus::Uint(settings.hardcore_start_cpm.into()),
+                reference_name: "hardcore_start_cpm".to_string(),
+            },
+            SettingsItem {
+                description: "Hardcore speed increase per minute (char/min)".to_string(),
+                status: SettingsStatus::Uint(settings.hardcore_ramp_cpm.into()),
+                reference_name: "hardcore_ramp_cpm".to_string(),
+            },
+            SettingsItem {
+                description: "Hardcore wrong keys allowed".to_string(),
+                status: SettingsStatus::Uint(settings.hardcore_mistakes.into()),
+                reference_name: "hardcore_mistakes".to_string(),
+            },
         ];
 
+        // character sets found in the data directories, after the built in ones
+        let custom_items = char_sets::custom_char_sets().into_iter().map(|set| SettingsItem {
+            description: if set.description.is_empty() {
+                set.name
+            } else {
+                format!("{} ({})", set.name, set.description)
+            },
+            status: SettingsStatus::Boolean(settings.custom_sets.contains(&set.id)),
+            reference_name: format!("{CUSTOM_SET_PREFIX}{}", set.id),
+        });
+        let position = loaded_items
+            .iter()
+            .position(|item| item.reference_name == "special_characters")
+            .map_or(loaded_items.len(), |i| i + 1);
+        loaded_items.splice(position..position, custom_items);
+
+        // numbers edited in the config file are brought back into range
+        for item in &mut loaded_items {
+            if let SettingsStatus::Uint(ref mut val) = item.status {
+                let range = settings_struct::Settings::number_range(&item.reference_name);
+                *val = (*val).clamp(*range.start(), *range.end());
+            }
+        }
+
         let mut _state = TableState::default();
         if !loaded_items.is_empty() {
             _state.select(Some(0));
@@ -102,13 +251,12 @@ impl Default for SettingsStateList {
             state: _state,
             items: loaded_items,
             last_selected: None,
-            settings_struct: settings,
         }
     }
 }
 
 impl SettingsItem {
-    fn to_table_row(&self) -> Row {
+    fn to_table_row(&self) -> Row<'_> {
         Row::new(vec![
             Cell::from(self.description.clone()),
             Cell::from(self.settings_status_to_str()),
@@ -116,10 +264,11 @@ impl SettingsItem {
     }
 
     fn settings_status_to_str(&self) -> String {
-        match self.status {
+        match &self.status {
             SettingsStatus::Uint(val) => val.to_string(),
+            SettingsStatus::Choice(index, options) => options[*index].to_string(),
             SettingsStatus::Boolean(val) => {
-                if val {
+                if *val {
                     "On".to_string()
                 } else {
                     "Off".to_string()
@@ -129,8 +278,20 @@ impl SettingsItem {
     }
 }
 
-impl SettingsStateList {
-    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+impl Page for SettingsStateList {
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Action {
+        match key_event.code {
+            KeyCode::Esc => return Action::Pop,
+            KeyCode::Char('j') | KeyCode::Down => self.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.previous(),
+            KeyCode::Char('h') | KeyCode::Left => self.edit_entry(false),
+            KeyCode::Char('l') | KeyCode::Right => self.edit_entry(true),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
         let instructions = Title::from(Line::from(vec![
             " quit: <esc> | down: ↓ or j | up: ↑ or k | toggle increase: → or l | toggle decrease: ← or h ".into(),
         ]));
@@ -177,6 +338,9 @@ impl SettingsStateList {
         StatefulWidget::render(table, area, buf, &mut self.state);
     }
 
+}
+
+impl SettingsStateList {
     pub fn next(&mut self) {
         let i = match self.state.selected() {
             Some(i) => {
@@ -214,16 +378,26 @@ impl SettingsStateList {
                 *val = !*val;
             }
             SettingsStatus::Uint(ref mut val) => {
+                let range = settings_struct::Settings::number_range(&selected_item.reference_name);
                 if increment {
-                    if *val < 255 {
+                    if *val < *range.end() {
                         *val += 1;
                     } else {
-                        *val = 0;
+                        *val = *range.start();
                     }
-                } else if *val > 0 {
+                } else if *val > *range.start() {
                     *val -= 1;
                 } else {
-                    *val = 255;
+                    *val = *range.end();
+                }
+            }
+            SettingsStatus::Choice(ref mut index, options) => {
+                if increment {
+                    *index = (*index + 1) % options.len();
+                } else if *index > 0 {
+                    *index -= 1;
+                } else {
+                    *index = options.len() - 1;
                 }
             }
         }
@@ -239,17 +413,17 @@ impl SettingsStateList {
             match item.reference_name.as_str() {
                 "total_time_sec" => {
                     if let SettingsStatus::Uint(val) = item.status {
-                        settings.total_time_sec = val;
+                        settings.total_time_sec = byte(val);
                     }
                 }
                 "history_length" => {
                     if let SettingsStatus::Uint(val) = item.status {
-                        settings.history_length = val;
+                        settings.history_length = byte(val);
                     }
                 }
                 "future_length" => {
                     if let SettingsStatus::Uint(val) = item.status {
-                        settings.future_length = val;
+                        settings.future_length = byte(val);
                     }
                 }
                 "large_char" => {
@@ -257,6 +431,59 @@ impl SettingsStateList {
                         settings.large_char = val;
                     }
                 }
+                "result_heatmap" => {
+                    if let SettingsStatus::Boolean(val) = item.status {
+                        settings.result_heatmap = val;
+                    }
+                }
+                "game_mode" => {
+                    if let SettingsStatus::Choice(index, _) = item.status {
+                        settings.game_mode = GameMode::SELECTABLE[index];
+                    }
+                }
+                "word_list" => {
+                    if let SettingsStatus::Choice(index, _) = item.status {
+                        settings.word_list = WordList::ALL[index];
+                    }
+                }
+                "quote_length" => {
+                    if let SettingsStatus::Choice(index, _) = item.status {
+                        settings.quote_length = QuoteLength::ALL[index];
+                    }
+                }
+                "ngram_list" => {
+                    if let SettingsStatus::Choice(index, _) = item.status {
+                        settings.ngram_list = NgramList::ALL[index];
+                    }
+                }
+                "code_language" => {
+                    if let SettingsStatus::Choice(index, _) = item.status {
+                        settings.code_language = CodeLanguage::ALL[index];
+                    }
+                }
+                "layout" => {
+                    if let SettingsStatus::Choice(index, options) = &item.status {
+                        settings.layout = options[*index].clone();
+                    }
+                }
+                "emulate_from" => {
+                    if let SettingsStatus::Choice(index, options) = &item.status {
+                        settings.emulate_from = match index {
+                            0 => None,
+                            _ => Some(options[*index].clone()),
+                        };
+                    }
+                }
+                "lesson_target_cpm" => {
+                    if let SettingsStatus::Uint(val) = item.status {
+                        settings.lesson_target_cpm = val;
+                    }
+                }
+                "lesson_target_accuracy" => {
+                    if let SettingsStatus::Uint(val) = item.status {
+                        settings.lesson_target_accuracy = byte(val);
+                    }
+                }
                 "lower_case_letters" => {
                     if let SettingsStatus::Boolean(val) = item.status {
                         settings.lower_case_letters = val;
@@ -282,6 +509,11 @@ impl SettingsStateList {
                         settings.special_characters = val;
                     }
                 }
+                "adaptive" => {
+                    if let SettingsStatus::Boolean(val) = item.status {
+                        settings.adaptive = val;
+                    }
+                }
                 "ten_finger_typing" => {
                     if let SettingsStatus::Boolean(val) = item.status {
                         settings.ten_finger_typing = val;
@@ -292,7 +524,28 @@ impl SettingsStateList {
                         settings.hardcore = val;
                     }
                 }
-                _ => {}
+                "hardcore_start_cpm" => {
+                    if let SettingsStatus::Uint(val) = item.status {
+                        settings.hardcore_start_cpm = byte(val);
+                    }
+                }
+                "hardcore_ramp_cpm" => {
+                    if let SettingsStatus::Uint(val) = item.status {
+                        settings.hardcore_ramp_cpm = byte(val);
+                    }
+                }
+                "hardcore_mistakes" => {
+                    if let SettingsStatus::Uint(val) = item.status {
+                        settings.hardcore_mistakes = byte(val);
+                    }
+                }
+                name => {
+                    if let (Some(id), SettingsStatus::Boolean(true)) =
+                        (name.strip_prefix(CUSTOM_SET_PREFIX), &item.status)
+                    {
+                        settings.custom_sets.push(id.to_string());
+                    }
+                }
             }
         }
         settings
@@ -1,24 +1,232 @@
+use std::ops::RangeInclusive;
+
 use confy;
+
+use crate::error::AppError;
 static APP_NAME: &str = "crabtype";
 static CONFIG_NAME: &str = "config";
 
-#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone, Copy)]
+#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone, Copy, PartialEq)]
+pub enum GameMode {
+    Characters,
+    Words,
+    Quote,
+    Lesson,
+    Ngrams,
+    Code,
+    // a text file or stdin, only chosen from the command line
+    Text,
+    // a book on the bookshelf, only chosen from the menu
+    Book,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 8] = [
+        GameMode::Characters,
+        GameMode::Words,
+        GameMode::Quote,
+        GameMode::Lesson,
+        GameMode::Ngrams,
+        GameMode::Code,
+        GameMode::Text,
+        GameMode::Book,
+    ];
+
+    /// the modes that can be chosen in the settings
+    pub const SELECTABLE: [GameMode; 6] = [
+        GameMode::Characters,
+        GameMode::Words,
+        GameMode::Quote,
+        GameMode::Lesson,
+        GameMode::Ngrams,
+        GameMode::Code,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Characters => "Characters",
+            GameMode::Words => "Words",
+            GameMode::Quote => "Quote",
+            GameMode::Lesson => "Lessons",
+            GameMode::Ngrams => "N-grams",
+            GameMode::Code => "Code",
+            GameMode::Text => "Text",
+            GameMode::Book => "Book",
+        }
+    }
+
+    /// whether the session ends when the text is finished instead of when the time is up
+    pub fn is_text_based(&self) -> bool {
+        matches!(
+            self,
+            GameMode::Quote | GameMode::Code | GameMode::Text | GameMode::Book
+        )
+    }
+
+    /// modes that are typed one character at a time instead of word by word
+    pub fn is_character_based(&self) -> bool {
+        matches!(self, GameMode::Characters | GameMode::Lesson)
+    }
+}
+
+#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone, Copy, PartialEq)]
+pub enum WordList {
+    Top200,
+    Top1k,
+    Top10k,
+}
+
+impl WordList {
+    pub const ALL: [WordList; 3] = [WordList::Top200, WordList::Top1k, WordList::Top10k];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WordList::Top200 => "Top 200",
+            WordList::Top1k => "Top 1k",
+            WordList::Top10k => "Top 10k",
+        }
+    }
+
+    /// file name (without extension) in `resources/words/`
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            WordList::Top200 => "top200",
+            WordList::Top1k => "top1k",
+            WordList::Top10k => "top10k",
+        }
+    }
+}
+
+#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone, Copy, PartialEq)]
+pub enum NgramList {
+    English,
+    Rust,
+}
+
+impl NgramList {
+    pub const ALL: [NgramList; 2] = [NgramList::English, NgramList::Rust];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NgramList::English => "English",
+            NgramList::Rust => "Rust",
+        }
+    }
+
+    /// file name (without extension) in `resources/ngrams/`
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            NgramList::English => "english",
+            NgramList::Rust => "rust",
+        }
+    }
+}
+
+#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone, Copy, PartialEq)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    C,
+    Shell,
+}
+
+impl CodeLanguage {
+    pub const ALL: [CodeLanguage; 4] = [
+        CodeLanguage::Rust,
+        CodeLanguage::Python,
+        CodeLanguage::C,
+        CodeLanguage::Shell,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodeLanguage::Rust => "Rust",
+            CodeLanguage::Python => "Python",
+            CodeLanguage::C => "C",
+            CodeLanguage::Shell => "Shell",
+        }
+    }
+
+    /// file name (without extension) in `resources/code/`
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            CodeLanguage::Rust => "rust",
+            CodeLanguage::Python => "python",
+            CodeLanguage::C => "c",
+            CodeLanguage::Shell => "shell",
+        }
+    }
+}
+
+#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone, Copy, PartialEq)]
+pub enum QuoteLength {
+    Short,
+    Medium,
+    Long,
+}
+
+impl QuoteLength {
+    pub const ALL: [QuoteLength; 3] = [QuoteLength::Short, QuoteLength::Medium, QuoteLength::Long];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuoteLength::Short => "Short",
+            QuoteLength::Medium => "Medium",
+            QuoteLength::Long => "Long",
+        }
+    }
+
+    /// bucket a quote by its number of characters
+    pub fn from_len(len: usize) -> QuoteLength {
+        if len < 80 {
+            QuoteLength::Short
+        } else if len < 160 {
+            QuoteLength::Medium
+        } else {
+            QuoteLength::Long
+        }
+    }
+}
+
+#[derive(Debug, serde_derive::Serialize, serde_derive::Deserialize, Clone)]
+#[serde(default)]
 pub struct Settings {
     pub total_time_sec: u8,
     pub history_length: u8,
     pub future_length: u8,
     pub large_char: bool,
+    pub result_heatmap: bool,
+    pub game_mode: GameMode,
+    pub word_list: WordList,
+    pub quote_length: QuoteLength,
+    pub ngram_list: NgramList,
+    pub code_language: CodeLanguage,
+    // name of a built in layout or of a file in the layouts directory
+    pub layout: String,
+    // the layout set in the OS, when practicing `layout` without switching to it
+    pub emulate_from: Option<String>,
+    // a lesson key is mastered at this speed (char/min) and accuracy (%)
+    pub lesson_target_cpm: u16,
+    pub lesson_target_accuracy: u8,
     pub lower_case_letters: bool,
     pub capital_letters: bool,
     pub numbers: bool,
     pub parenthesis: bool,
     pub special_characters: bool,
+    // ids of the enabled character sets from the `charsets` directories
+    pub custom_sets: Vec<String>,
+    // draw characters the user often misses more often
+    pub adaptive: bool,
     pub ten_finger_typing: bool,
 
-    // TODO: remove time aspect and create stay alive mode.
-    // Should exit game if character/minute is too slow or
-    // if a wrong word is typed:
+    // stay alive mode without a timer: the game ends when the
+    // characters per minute drop below a rising threshold or
+    // when too many wrong keys are pressed
     pub hardcore: bool,
+    pub hardcore_start_cpm: u8,
+    // added to the required characters per minute every minute
+    pub hardcore_ramp_cpm: u8,
+    pub hardcore_mistakes: u8,
 }
 
 impl Default for Settings {
@@ -28,16 +236,28 @@ impl Default for Settings {
             history_length: 3,
             future_length: 3,
             large_char: true,
+            result_heatmap: false,
+            game_mode: GameMode::Characters,
+            word_list: WordList::Top200,
+            quote_length: QuoteLength::Short,
+            ngram_list: NgramList::English,
+            code_language: CodeLanguage::Rust,
+            layout: "QWERTY".to_string(),
+            emulate_from: None,
+            lesson_target_cpm: 150,
+            lesson_target_accuracy: 95,
             lower_case_letters: true,
             capital_letters: false,
             numbers: false,
             parenthesis: false,
             special_characters: false,
+            custom_sets: vec![],
+            adaptive: false,
             ten_finger_typing: false,
-            // TODO: remove time aspect and create stay alive mode.
-            // Should exit game if character/minute is too slow or
-            // if a wrong word is typed:
             hardcore: false,
+            hardcore_start_cpm: 60,
+            hardcore_ramp_cpm: 20,
+            hardcore_mistakes: 1,
         }
     }
 }
@@ -46,13 +266,20 @@ impl Settings {
     pub fn write_config(cfg: &Settings) -> Result<(), confy::ConfyError> {
         confy::store(APP_NAME, CONFIG_NAME, cfg)
     }
-    pub fn read_config() -> Result<Settings, confy::ConfyError> {
-        let cfg = confy::load(APP_NAME, CONFIG_NAME);
-
-        // TODO: Make it more obvious if a bad config has been read
-        match cfg {
-            Ok(config) => Ok(config),
-            Err(_) => Ok(Settings::default()),
+    /// the values a number setting can take, by its name in the config file
+    pub fn number_range(key: &str) -> RangeInclusive<u16> {
+        match key {
+            "lesson_target_cpm" => 1..=1000,
+            "lesson_target_accuracy" => 0..=100,
+            // a hardcore game ends on the wrong key that uses up the last one
+            "hardcore_mistakes" => 1..=255,
+            _ => 0..=255,
         }
     }
+
+    /// the stored settings, a config file that can't be parsed is an error
+    /// so the caller can tell the user before falling back to the defaults
+    pub fn read_config() -> Result<Settings, AppError> {
+        Ok(confy::load(APP_NAME, CONFIG_NAME)?)
+    }
 }
@@ -0,0 +1,180 @@
+use ratatui::{prelude::*, widgets::*};
+
+use crate::char_lib::fingers::Finger;
+use crate::char_lib::layouts::KeyboardLayout;
+use crate::history::session_history::KeyResult;
+
+// horizontal offset of each row, like the stagger on a physical keyboard
+static ROW_OFFSETS: [u16; 4] = [0, 2, 3, 5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HeatmapMetric {
+    #[default]
+    ErrorRate,
+    Latency,
+}
+
+impl HeatmapMetric {
+    pub fn toggle(&self) -> HeatmapMetric {
+        match self {
+            HeatmapMetric::ErrorRate => HeatmapMetric::Latency,
+            HeatmapMetric::Latency => HeatmapMetric::ErrorRate,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapMetric::ErrorRate => "error rate",
+            HeatmapMetric::Latency => "mean latency",
+        }
+    }
+}
+
+/// a keyboard where every key is colored by its error rate or latency
+#[derive(Debug)]
+pub struct KeyboardHeatmap {
+    // results merged per physical key
+    keys: Vec<KeyResult>,
+    metric: HeatmapMetric,
+    layout: KeyboardLayout,
+}
+
+impl KeyboardHeatmap {
+    pub fn new(
+        results: &[KeyResult],
+        metric: HeatmapMetric,
+        layout: &KeyboardLayout,
+    ) -> KeyboardHeatmap {
+        let mut keys: Vec<KeyResult> = vec![];
+        for result in results {
+            let key = layout.base_key(result.key);
+            match keys.iter_mut().find(|k| k.key == key) {
+                Some(existing) => existing.merge(result),
+                None => keys.push(KeyResult {
+                    key,
+                    ..result.clone()
+                }),
+            }
+        }
+        KeyboardHeatmap {
+            keys,
+            metric,
+            layout: layout.clone(),
+        }
+    }
+
+    /// width and height needed to draw the keyboard and its legend
+    pub fn size() -> (u16, u16) {
+        (ROW_OFFSETS[1] + 13 * 4, 9)
+    }
+
+    fn value(&self, key: &KeyResult) -> Option<f64> {
+        match self.metric {
+            HeatmapMetric::ErrorRate if key.hits + key.misses > 0 => Some(key.error_rate()),
+            HeatmapMetric::Latency if key.hits > 0 => Some(key.mean_latency_ms()),
+            _ => None,
+        }
+    }
+
+    fn range(&self) -> (f64, f64) {
+        let values: Vec<f64> = self.keys.iter().filter_map(|k| self.value(k)).collect();
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(0.0, f64::max);
+        match self.metric {
+            // an error free key is always green
+            HeatmapMetric::ErrorRate => (0.0, max),
+            HeatmapMetric::Latency => (min.min(max), max),
+        }
+    }
+
+    /// green for the best keys, through yellow, to red for the worst
+    fn color(&self, value: f64, (min, max): (f64, f64)) -> Color {
+        let t = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let red = (66.0 + 124.0 * (2.0 * t).min(1.0)) as u8;
+        let green = (190.0 - 124.0 * (2.0 * t - 1.0).max(0.0)) as u8;
+        Color::Rgb(red, green, 66)
+    }
+
+    fn legend(&self, (min, max): (f64, f64)) -> String {
+        match self.metric {
+            HeatmapMetric::ErrorRate => {
+                format!("{}: {:.0}% - {:.0}%", self.metric.name(), min * 100.0, max * 100.0)
+            }
+            HeatmapMetric::Latency => {
+                format!("{}: {:.0}ms - {:.0}ms", self.metric.name(), min, max)
+            }
+        }
+    }
+}
+
+impl Widget for KeyboardHeatmap {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let range = self.range();
+        for (row, keys) in self.layout.rows().iter().enumerate() {
+            let y = area.y + 2 * row as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            for (column, key) in keys.iter().copied().enumerate() {
+                let x = area.x + ROW_OFFSETS[row] + 4 * column as u16;
+                if x + 3 > area.right() {
+                    break;
+                }
+                let style = match self.keys.iter().find(|k| k.key == key) {
+                    Some(result) => match self.value(result) {
+                        Some(value) => Style::new().fg(Color::Black).bg(self.color(value, range)),
+                        None => Style::new().fg(Color::Gray).bg(Color::DarkGray),
+                    },
+                    None => Style::new().fg(Color::Gray).bg(Color::DarkGray),
+                };
+                buf.set_string(x, y, format!(" {key} "), style);
+            }
+        }
+
+        let legend_y = area.y + 2 * self.layout.rows().len() as u16;
+        if legend_y < area.bottom() {
+            Paragraph::new(self.legend(range))
+                .render(Rect::new(area.x, legend_y, area.width, 1), buf);
+        }
+    }
+}
+
+/// a keyboard where every key has the color of the finger that types it,
+/// with the key to press next highlighted
+#[derive(Debug)]
+pub struct FingerKeyboard<'a> {
+    pub target: char,
+    pub layout: &'a KeyboardLayout,
+}
+
+impl Widget for FingerKeyboard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let target = self.layout.base_key(self.target);
+        for (row, keys) in self.layout.rows().iter().enumerate() {
+            let y = area.y + 2 * row as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            for (column, key) in keys.iter().copied().enumerate() {
+                let x = area.x + ROW_OFFSETS[row] + 4 * column as u16;
+                if x + 3 > area.right() {
+                    break;
+                }
+                let color = Finger::for_position(row, column).color();
+                let style = if key == target {
+                    Style::new()
+                        .fg(Color::Black)
+                        .bg(color)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::new().fg(color)
+                };
+                buf.set_string(x, y, format!(" {key} "), style);
+            }
+        }
+    }
+}
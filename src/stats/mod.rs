@@ -0,0 +1,2 @@
+pub mod keyboard;
+pub mod stats_page;
@@ -0,0 +1,275 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+use crate::char_lib::layouts::{self, KeyboardLayout};
+use crate::history::session_history::{self, SessionRecord};
+use crate::settings::settings_struct::{GameMode, Settings};
+use crate::stats::keyboard::{HeatmapMetric, KeyboardHeatmap};
+use crate::tui::screen::{Action, Page};
+
+#[derive(Debug, Default)]
+pub struct StatsPage {
+    pub sessions: Vec<SessionRecord>,
+    // index into `mode_options`/`set_options`, 0 means no filter
+    pub mode_filter: usize,
+    pub set_filter: usize,
+    pub heatmap_metric: HeatmapMetric,
+    pub layout: KeyboardLayout,
+}
+
+impl StatsPage {
+    /// reads the session history from disk and clears the filters
+    pub fn load() -> StatsPage {
+        StatsPage {
+            sessions: session_history::load_sessions(),
+            mode_filter: 0,
+            set_filter: 0,
+            heatmap_metric: HeatmapMetric::default(),
+            layout: layouts::load_layout(&Settings::read_config().unwrap_or_default().layout),
+        }
+    }
+
+    fn mode_options(&self) -> Vec<Option<GameMode>> {
+        let mut options = vec![None];
+        options.extend(GameMode::ALL.iter().map(|mode| Some(*mode)));
+        options
+    }
+
+    /// every character set combination played in the selected mode
+    fn set_options(&self) -> Vec<Option<String>> {
+        let mode = self.mode_options()[self.mode_filter];
+        let mut options = vec![None];
+        for session in &self.sessions {
+            let label = Some(session.character_set_label());
+            if mode.is_none_or(|m| m == session.mode) && !options.contains(&label) {
+                options.push(label);
+            }
+        }
+        options
+    }
+
+    pub fn next_mode_filter(&mut self) {
+        self.mode_filter = (self.mode_filter + 1) % self.mode_options().len();
+        self.set_filter = 0;
+    }
+
+    pub fn next_set_filter(&mut self) {
+        self.set_filter = (self.set_filter + 1) % self.set_options().len();
+    }
+
+    pub fn toggle_heatmap_metric(&mut self) {
+        self.heatmap_metric = self.heatmap_metric.toggle();
+    }
+
+    pub fn filtered_sessions(&self) -> Vec<&SessionRecord> {
+        let mode = self.mode_options()[self.mode_filter];
+        let set = self.set_options()[self.set_filter].clone();
+        self.sessions
+            .iter()
+            .filter(|s| mode.is_none_or(|m| m == s.mode))
+            .filter(|s| set.as_ref().is_none_or(|l| *l == s.character_set_label()))
+            .collect()
+    }
+
+}
+
+impl Page for StatsPage {
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Action {
+        match key_event.code {
+            KeyCode::Esc => return Action::Pop,
+            KeyCode::Char('m') => self.next_mode_filter(),
+            KeyCode::Char('c') => self.next_set_filter(),
+            KeyCode::Char('h') => self.toggle_heatmap_metric(),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let instructions = Title::from(Line::from(vec![
+            " back: <esc> | mode filter: m | character set filter: c | heatmap: h ".into(),
+        ]));
+        let block = Block::default()
+            .title(Title::from(" Statistics ".bold()).alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Blue).bg(Color::Black))
+            .border_set(border::THICK);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let (summary_layout, chart_layout, bottom_layout) = self.split_stats_layout(inner);
+        let sessions = self.filtered_sessions();
+
+        // Summary paragraph
+        let mode_label = self.mode_options()[self.mode_filter].map_or("All", |m| m.name());
+        let set_label = self.set_options()[self.set_filter]
+            .clone()
+            .unwrap_or("All".to_string());
+        let best = sessions
+            .iter()
+            .map(|s| s.metrics.net_wpm)
+            .fold(0.0, f64::max);
+        let average = if sessions.is_empty() {
+            0.0
+        } else {
+            sessions.iter().map(|s| s.metrics.net_wpm).sum::<f64>() / sessions.len() as f64
+        };
+        Paragraph::new(vec![
+            Line::from(format!("Mode: {mode_label} | Character sets: {set_label}")),
+            Line::from(format!(
+                "Sessions: {} | best net WPM: {:.1} | average net WPM: {:.1}",
+                sessions.len(),
+                best,
+                average
+            )),
+        ])
+        .centered()
+        .render(summary_layout, buf);
+
+        if sessions.is_empty() {
+            Paragraph::new("No finished sessions yet")
+                .centered()
+                .render(chart_layout[0], buf);
+            return;
+        }
+
+        let wpm: Vec<(f64, f64)> = sessions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i as f64 + 1.0, s.metrics.net_wpm))
+            .collect();
+        let accuracy: Vec<(f64, f64)> = sessions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i as f64 + 1.0, s.metrics.raw_accuracy))
+            .collect();
+        let first = sessions[0].local_time().format("%d/%m").to_string();
+        let last = sessions[sessions.len() - 1]
+            .local_time()
+            .format("%d/%m")
+            .to_string();
+        let max_wpm = (best / 10.0).ceil().max(1.0) * 10.0;
+
+        line_chart(
+            " Net WPM ",
+            &wpm,
+            [0.0, max_wpm],
+            (first.clone(), last.clone()),
+            Color::Yellow,
+        )
+        .render(chart_layout[0], buf);
+        line_chart(
+            " Accuracy (%) ",
+            &accuracy,
+            [0.0, 100.0],
+            (first, last),
+            Color::Green,
+        )
+        .render(chart_layout[1], buf);
+
+        // Key heatmap over the filtered sessions
+        let heatmap = KeyboardHeatmap::new(
+            &session_history::aggregate_key_results(sessions.iter().copied()),
+            self.heatmap_metric,
+            &self.layout,
+        );
+        let heatmap_block = Block::new()
+            .borders(Borders::ALL)
+            .title(format!(" Keys by {} ", self.heatmap_metric.name()));
+        let heatmap_area = heatmap_block.inner(bottom_layout[0]);
+        heatmap_block.render(bottom_layout[0], buf);
+        heatmap.render(heatmap_area, buf);
+
+        // Sparkline of the most recent sessions that fit in the area
+        let sparkline_layout = bottom_layout[1];
+        let recent: Vec<u64> = sessions
+            .iter()
+            .rev()
+            .take(sparkline_layout.width.saturating_sub(2).into())
+            .rev()
+            .map(|s| s.metrics.net_wpm.round() as u64)
+            .collect();
+        Sparkline::default()
+            .block(Block::new().borders(Borders::ALL).title(" Recent net WPM "))
+            .data(&recent)
+            .style(Style::default().fg(Color::Yellow))
+            .render(sparkline_layout, buf);
+    }
+
+}
+
+impl StatsPage {
+    fn split_stats_layout(&self, area: Rect) -> (Rect, Rc<[Rect]>, Rc<[Rect]>) {
+        // split stats in:
+        // +---------------------------------+
+        // |             Summary             |
+        // +----------------+----------------+
+        // |   WPM chart    | Accuracy chart |
+        // +----------------+----------------+
+        // |  Key heatmap   |   Sparkline    |
+        // +---------------------------------+
+
+        let (heatmap_width, heatmap_height) = KeyboardHeatmap::size();
+        let outer_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(heatmap_height + 2),
+            ])
+            .split(area);
+
+        let chart_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(outer_layout[1]);
+
+        let bottom_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Length(heatmap_width + 2), Constraint::Min(10)])
+            .split(outer_layout[2]);
+
+        (outer_layout[0], chart_layout, bottom_layout)
+    }
+}
+
+/// a line chart over the session number, labelled with the first and last date
+fn line_chart<'a>(
+    title: &'a str,
+    data: &'a [(f64, f64)],
+    y_bounds: [f64; 2],
+    dates: (String, String),
+    color: Color,
+) -> Chart<'a> {
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data);
+    Chart::new(vec![dataset])
+        .block(Block::new().borders(Borders::ALL).title(title))
+        .x_axis(
+            Axis::default()
+                .bounds([1.0, (data.len() as f64).max(2.0)])
+                .labels(vec![Span::from(dates.0), Span::from(dates.1)]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds(y_bounds)
+                .labels(vec![
+                    Span::from(format!("{:.0}", y_bounds[0])),
+                    Span::from(format!("{:.0}", y_bounds[1])),
+                ]),
+        )
+}
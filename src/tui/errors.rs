@@ -0,0 +1,32 @@
+use std::panic;
+
+use color_eyre::{config::HookBuilder, eyre};
+use crossterm::terminal::is_raw_mode_enabled;
+
+use crate::tui::tui_tools;
+
+/// installs the color_eyre panic and error hooks, which leave the alternate
+/// screen before printing so the report is readable
+pub fn install_hooks() -> color_eyre::Result<()> {
+    let (panic_hook, eyre_hook) = HookBuilder::default().into_hooks();
+
+    let panic_hook = panic_hook.into_panic_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        restore_terminal();
+        panic_hook(panic_info);
+    }));
+
+    let eyre_hook = eyre_hook.into_eyre_hook();
+    eyre::set_hook(Box::new(move |error| {
+        restore_terminal();
+        eyre_hook(error)
+    }))?;
+    Ok(())
+}
+
+// only touch the terminal when the tui has set it up
+fn restore_terminal() {
+    if is_raw_mode_enabled().unwrap_or(false) {
+        let _ = tui_tools::restore();
+    }
+}
@@ -1,2 +1,4 @@
+pub mod errors;
 pub mod pages;
+pub mod screen;
 pub mod tui_tools;
@@ -1,27 +1,152 @@
-use core::panic;
-use std::cell::RefCell;
 use std::io;
 use std::time::Duration;
 use std::time::Instant;
 
+use crate::books::bookshelf::Book;
+use crate::char_lib::load_text::CustomText;
+use crate::cli::args::PlayArgs;
+use crate::error::AppError;
+use crate::game::clock::MonotonicClock;
+use crate::settings::settings_struct::Settings;
+use crate::tui::screen::{Action, Page, Screen};
 use crate::tui::tui_tools;
-use crate::{game::game_page, menu::menu_page, settings::settings_page};
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crate::{game::game_page, menu::menu_page, notice::notice_page};
+use crate::{replay::replay_page, settings::settings_page, stats::stats_page};
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
 use ratatui::prelude::*;
 
 #[derive(Debug, Default)]
-pub struct App<'a> {
+pub struct App {
     exit: bool,
-    // app state:
-    state: &'a str,
-    gamestruct: RefCell<game_page::GameLogic>,
-    settings_select: RefCell<settings_page::SettingsStateList>,
+    // navigation stack, the menu is always at the bottom and the page on top gets the keys
+    screens: Vec<Screen>,
+    // the page the app opens on
+    start_screen: Screen,
+    menu: menu_page::MenuPage,
+    game: Option<game_page::GameLogic>,
+    settings_select: settings_page::SettingsStateList,
+    stats: stats_page::StatsPage,
+    replay: replay_page::ReplayPage,
+    notice: notice_page::NoticePage,
+    // text given on the command line, played instead of the configured mode
+    text: Option<CustomText>,
+    // book given on the command line, the first game starts from its bookmark
+    book: Option<Book>,
+    // settings given on the command line, they take precedence over the config
+    overrides: PlayArgs,
 }
 
-impl<'a> App<'a> {
+impl App {
+    /// an app that starts on `start_screen`
+    pub fn new(
+        start_screen: Screen,
+        overrides: PlayArgs,
+        text: Option<CustomText>,
+        book: Option<Book>,
+    ) -> App {
+        App {
+            start_screen,
+            text,
+            book,
+            overrides,
+            ..Default::default()
+        }
+    }
+
+    /// shows a problem on the notice page, on top of the current page
+    fn notify(&mut self, error: AppError) {
+        self.notice.notices.push(error);
+        if self.screens.last() != Some(&Screen::Notice) {
+            self.screens.push(Screen::Notice);
+        }
+    }
+
+    /// the stored settings, the defaults with a notice if the config is broken
+    fn read_settings(&mut self) -> Settings {
+        Settings::read_config().unwrap_or_else(|error| {
+            self.notify(error);
+            Settings::default()
+        })
+    }
+
+    /// starts a new game with the stored settings and the command line overrides,
+    /// stays on the current page if the game can't be set up
+    fn start_game(&mut self, book: Option<Book>) {
+        let mut settings = self.read_settings();
+        self.overrides.apply(&mut settings);
+        let clock = Box::new(MonotonicClock::default());
+        let mut game = match game_page::GameLogic::new(settings, clock) {
+            Ok(game) => game,
+            Err(error) => return self.notify(error),
+        };
+        if let Some(book) = book {
+            game.use_book(book);
+        } else if let Some(text) = &self.text {
+            game.use_text(text.clone());
+        }
+        if let Err(error) = game.reset() {
+            return self.notify(error);
+        }
+        self.game = Some(game);
+        self.push(Screen::Game);
+    }
+
+    /// opens `screen` on top of the current page
+    fn push(&mut self, screen: Screen) {
+        match screen {
+            Screen::Stats => self.stats = stats_page::StatsPage::load(),
+            Screen::Replay => {
+                let settings = self.read_settings();
+                self.replay = replay_page::ReplayPage::load(settings);
+            }
+            _ => {}
+        }
+        self.screens.push(screen);
+    }
+
+    /// goes back to the page below the current one, the menu stays open
+    fn pop(&mut self) {
+        if self.screens.len() <= 1 {
+            return;
+        }
+        if self.screens.pop() == Some(Screen::Game) {
+            // the next game continues the text and the books where this one stopped
+            if let Some(game) = self.game.take() {
+                if self.text.is_some() {
+                    self.text = game.remaining_text();
+                }
+            }
+            self.menu = menu_page::MenuPage::load();
+        }
+    }
+
+    fn page_mut(&mut self, screen: Screen) -> Option<&mut dyn Page> {
+        match screen {
+            Screen::Menu => Some(&mut self.menu),
+            Screen::Settings => Some(&mut self.settings_select),
+            Screen::Game => self.game.as_mut().map(|game| game as &mut dyn Page),
+            Screen::Stats => Some(&mut self.stats),
+            Screen::Replay => Some(&mut self.replay),
+            Screen::Notice => Some(&mut self.notice),
+        }
+    }
+
     /// runs the application's main loop until the user quits
     pub fn run(&mut self, terminal: &mut tui_tools::Tui) -> io::Result<()> {
-        self.state = "menu";
+        self.screens = vec![Screen::Menu];
+        self.menu = menu_page::MenuPage::load();
+        match self.start_screen {
+            Screen::Game => {
+                let book = self.book.take();
+                self.start_game(book)
+            }
+            Screen::Menu => {}
+            screen => self.push(screen),
+        }
+        // a game reports a broken config itself, other pages only use parts of it
+        if self.game.is_none() {
+            self.read_settings();
+        }
         let tick_rate = Duration::from_millis(100); // Update every second
         let mut last_tick = Instant::now();
 
@@ -33,9 +158,14 @@ impl<'a> App<'a> {
             if crossterm::event::poll(timeout)? {
                 self.handle_events()?;
             }
+            self.collect_errors();
 
             if last_tick.elapsed() >= tick_rate {
-                self.gamestruct.borrow_mut().get_time(); // Update the game timer
+                for screen in self.screens.clone() {
+                    if let Some(page) = self.page_mut(screen) {
+                        page.tick(); // Update the game timer
+                    }
+                }
                 last_tick = Instant::now();
                 terminal.draw(|frame| self.render_frame(frame))?;
             }
@@ -43,7 +173,20 @@ impl<'a> App<'a> {
         Ok(())
     }
 
-    fn render_frame(&self, frame: &mut Frame) {
+    /// shows the problems the pages on the stack ran into since the last call
+    fn collect_errors(&mut self) {
+        for screen in self.screens.clone() {
+            let errors = match self.page_mut(screen) {
+                Some(page) => page.take_errors(),
+                None => vec![],
+            };
+            for error in errors {
+                self.notify(error);
+            }
+        }
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame) {
         frame.render_widget(self, frame.size());
     }
 
@@ -59,53 +202,20 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// passes the key to the page on top of the stack and does what it asks for
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        // options for the different pages:
-        // Menu:
-        if self.state == "menu" {
-            match key_event.code {
-                KeyCode::Char('q') => self.exit(),
-                KeyCode::Char('s') => {
-                    self.state = "settings";
-                }
-                KeyCode::Char('b') => {
-                    self.state = "game";
-                    self.gamestruct = game_page::GameLogic::default().into();
-                    self.gamestruct.borrow_mut().reset();
-                }
-                _ => {}
-            }
-
-        // Settings:
-        } else if self.state == "settings" {
-            match key_event.code {
-                KeyCode::Esc => {
-                    self.state = "menu";
-                }
-                KeyCode::Char('j') | KeyCode::Down => self.settings_select.borrow_mut().next(),
-                KeyCode::Char('k') | KeyCode::Up => self.settings_select.borrow_mut().previous(),
-                KeyCode::Char('h') | KeyCode::Left => {
-                    self.settings_select.borrow_mut().edit_entry(false)
-                }
-                KeyCode::Char('l') | KeyCode::Right => {
-                    self.settings_select.borrow_mut().edit_entry(true)
-                }
-                _ => {}
-            }
-
-        // Game:
-        } else if self.state == "game" {
-            self.gamestruct.borrow_mut().get_time();
-            match key_event.code {
-                KeyCode::Esc => {
-                    self.state = "menu";
-                }
-                KeyCode::Char(' ') => self.gamestruct.borrow_mut().reset(),
-                KeyCode::Char(code) => self.gamestruct.borrow_mut().compare_pressed_char(code),
-                _ => {}
-            }
-        } else {
-            panic!("Unexpected state");
+        let screen = self.screens.last().copied().unwrap_or_default();
+        let action = match self.page_mut(screen) {
+            Some(page) => page.handle_key_event(key_event),
+            None => Action::Pop,
+        };
+        match action {
+            Action::None => {}
+            Action::Push(screen) => self.push(screen),
+            Action::Pop => self.pop(),
+            Action::StartGame(book) => self.start_game(book),
+            Action::Notify(error) => self.notify(error),
+            Action::Quit => self.exit(),
         }
     }
 
@@ -114,17 +224,19 @@ impl<'a> App<'a> {
     }
 }
 
-impl Widget for &App<'_> {
+impl Widget for &mut App {
+    /// draws the topmost full page and the overlays above it
     fn render(self, area: Rect, buf: &mut Buffer) {
-        match self.state {
-            "menu" => menu_page::render(area, buf),
-            "settings" => {
-                self.settings_select.borrow_mut().render(area, buf);
-            }
-            "game" => {
-                self.gamestruct.borrow_mut().render(area, buf);
+        let base = self
+            .screens
+            .iter()
+            .rposition(|screen| !screen.is_overlay())
+            .unwrap_or(0);
+        let visible: Vec<Screen> = self.screens[base..].to_vec();
+        for screen in visible {
+            if let Some(page) = self.page_mut(screen) {
+                page.render(area, buf);
             }
-            _ => {}
         }
     }
 }
@@ -0,0 +1,55 @@
+use crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+
+use crate::books::bookshelf::Book;
+use crate::error::AppError;
+
+/// the pages of the app, kept on a navigation stack with the menu at the bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Screen {
+    #[default]
+    Menu,
+    Settings,
+    Game,
+    Stats,
+    Replay,
+    Notice,
+}
+
+impl Screen {
+    /// whether the page is drawn on top of the page below it instead of replacing it
+    pub fn is_overlay(&self) -> bool {
+        matches!(self, Screen::Notice)
+    }
+}
+
+/// what the app does after a page handled a key press
+#[derive(Debug, Default)]
+pub enum Action {
+    #[default]
+    None,
+    /// opens a page on top of the current one
+    Push(Screen),
+    /// goes back to the page below
+    Pop,
+    /// starts a game, from the bookmark of a book if one is given
+    StartGame(Option<Book>),
+    /// shows a problem on the notice page
+    Notify(AppError),
+    Quit,
+}
+
+/// a page of the app, it draws itself and turns key presses into actions
+pub trait Page {
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Action;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer);
+
+    /// called on every tick while the page is on the navigation stack
+    fn tick(&mut self) {}
+
+    /// problems the page ran into outside of a key press, shown on the notice page
+    fn take_errors(&mut self) -> Vec<AppError> {
+        vec![]
+    }
+}